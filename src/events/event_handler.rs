@@ -0,0 +1,51 @@
+use poise::serenity_prelude as serenity;
+
+use crate::services::reaction_live::schedule_live_update;
+use crate::services::reaction_watch::{forget_deleted_messages, record_reaction_event};
+use crate::services::reaction_watch::signup::notify_promotion;
+use crate::{Data, Error};
+
+/// Handles gateway events other than interactions, which poise dispatches itself.
+pub async fn event_handler(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    framework: poise::FrameworkContext<'_, Data, Error>,
+    data: &Data,
+) -> Result<(), Error> {
+    let message_id = match event {
+        serenity::FullEvent::ReactionAdd { add_reaction: reaction }
+        | serenity::FullEvent::ReactionRemove { removed_reaction: reaction }
+        | serenity::FullEvent::ReactionRemoveEmoji { removed_reactions: reaction } => reaction.message_id,
+        serenity::FullEvent::ReactionRemoveAll { removed_from_message_id, .. } => *removed_from_message_id,
+        serenity::FullEvent::MessageDelete { deleted_message_id, .. } => {
+            forget_deleted(data, &[*deleted_message_id]).await;
+            return Ok(());
+        }
+        serenity::FullEvent::MessageDeleteBulk { multiple_deleted_messages_ids, .. } => {
+            forget_deleted(data, multiple_deleted_messages_ids).await;
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    // 記録に失敗しても他のイベント処理は止めない
+    match record_reaction_event(&data.watches, event, framework.bot_id).await {
+        Ok(Some(promotion)) => {
+            if let Err(e) = notify_promotion(&ctx.http, &promotion).await {
+                log::error!("Failed to notify sign-up promotion: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("Failed to record reaction event: {}", e),
+    }
+
+    schedule_live_update(&data.live_summaries, &data.live_updates, message_id).await;
+    Ok(())
+}
+
+/// Stops watching deleted messages, so that their events do not stay in the store.
+async fn forget_deleted(data: &Data, message_ids: &[serenity::MessageId]) {
+    if let Err(e) = forget_deleted_messages(&data.watches, message_ids).await {
+        log::error!("Failed to forget deleted messages: {}", e);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod reaction_grouping_users_context_menu;
+pub mod reaction_report_context_menu;
 pub mod reaction_users_context_menu;
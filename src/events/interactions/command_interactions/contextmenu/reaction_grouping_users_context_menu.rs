@@ -1,7 +1,8 @@
 use anyhow::Result;
 use crate::Context;
-use crate::services::reaction_users::process_reaction_members;
-use crate::services::reaction_users::types::ReactionUsersParameter;
+use crate::events::interactions::components::report_toolbar_component::send_report_with_toolbar;
+use crate::services::reaction_users::filter::{ReactionFilter, RoleFilter, UserFilter};
+use crate::services::reaction_users::types::{OutputFormat, ReactionUsersParameter, UserSort};
 
 /// Handle the reaction members context menu command
 #[poise::command(
@@ -25,14 +26,15 @@ pub async fn get_reaction_grouping_members(
         is_reaction_grouping: true,
         is_author_include: false,
         is_show_count: false,
+        output_format: OutputFormat::Text,
+        reaction_filter: ReactionFilter::default(),
+        role_filter: RoleFilter::default(),
+        user_filter: UserFilter::default(),
+        sort: UserSort::default(),
     };
 
-    let response = process_reaction_members(
-        ctx, 
-        &parameter
-    ).await?;
-
-    ctx.say(response.content).await?;
+    // ツールバーで表示を切り替えられるようにする
+    send_report_with_toolbar(ctx, parameter).await?;
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use poise::Modal;
+use crate::ApplicationContext;
+use crate::events::interactions::components::report_toolbar_component::send_report;
+use crate::events::interactions::modal::reaction_report_modal::ReactionReportModal;
+
+/// Handle the "Reaction report…" context menu command
+///
+/// Opens a modal with the options of the slash command, then sends the report.
+#[poise::command(
+    context_menu_command = "Reaction report…",
+    name_localized("ja", "リアクション集計（詳細設定）…"),
+    ephemeral
+)]
+pub async fn reaction_report(
+    ctx: ApplicationContext<'_>,
+    message: poise::serenity_prelude::Message,
+) -> Result<(), crate::Error> {
+
+    // The modal has to be the first response, so the interaction is not deferred
+    let Some(modal) = ReactionReportModal::execute(ctx).await? else {
+        // Closed or timed out
+        return Ok(());
+    };
+
+    let ctx = crate::Context::Application(ctx);
+    let result = match modal.to_parameter(ctx, message).await {
+        Ok(parameter) => send_report(ctx, parameter).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        ctx.say(format!("⚠️ Error: {}", e)).await?;
+    }
+
+    Ok(())
+}
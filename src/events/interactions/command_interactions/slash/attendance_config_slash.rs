@@ -0,0 +1,160 @@
+use poise::serenity_prelude::GuildId;
+
+use crate::services::guild_config::GuildConfig;
+use crate::services::reaction_attendance::{AttendanceEmoji, AttendanceStatus};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /attendance_config slash command
+///
+/// Only the subcommands can be run.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("attendance_config_set", "attendance_config_remove", "attendance_config_show", "attendance_config_reset"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD",
+    name_localized("ja", "出欠設定"),
+    description_localized("ja", "出欠確認で使う絵文字と出欠の対応を設定します。")
+)]
+pub async fn attendance_config(_ctx: Context<'_>) -> Result<(), crate::Error> {
+    Ok(())
+}
+
+/// Map an emoji to an attendance status
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "set",
+    name_localized("ja", "設定"),
+    description_localized("ja", "絵文字に出欠を割り当てます。"),
+    ephemeral
+)]
+pub async fn attendance_config_set(
+    ctx: Context<'_>,
+
+    #[description = "The emoji, e.g. ✅, :thumbsup: or <:name:id>."]
+    #[description_localized("ja", "絵文字。例: ✅、:thumbsup:、<:name:id>")]
+    emoji: String,
+
+    #[description = "The status the emoji stands for."]
+    #[description_localized("ja", "絵文字が表す出欠")]
+    status: AttendanceStatus,
+) -> Result<(), crate::Error> {
+    let mut result = Ok(());
+    let emojis = update_config(ctx, |config| result = config.set_attendance_emoji(&emoji, status)).await?;
+
+    match result {
+        Ok(()) => ctx.say(format!("✅ Saved.\n{}", to_mapping_text(&emojis))).await?,
+        Err(e) => ctx.say(format!("⚠️ Error: {}", e)).await?,
+    };
+
+    Ok(())
+}
+
+/// Stop counting an emoji
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "remove",
+    name_localized("ja", "削除"),
+    description_localized("ja", "絵文字の割り当てを削除します。"),
+    ephemeral
+)]
+pub async fn attendance_config_remove(
+    ctx: Context<'_>,
+
+    #[description = "The emoji to stop counting."]
+    #[description_localized("ja", "集計しない絵文字")]
+    emoji: String,
+) -> Result<(), crate::Error> {
+    let mut is_removed = false;
+    let emojis = update_config(ctx, |config| is_removed = config.remove_attendance_emoji(&emoji)).await?;
+
+    if is_removed {
+        ctx.say(format!("✅ Removed.\n{}", to_mapping_text(&emojis))).await?;
+    } else {
+        ctx.say(format!("⚠️ Error: {} is not mapped to a status.", emoji)).await?;
+    }
+
+    Ok(())
+}
+
+/// Show the emojis of this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "show",
+    name_localized("ja", "表示"),
+    description_localized("ja", "このサーバーの絵文字と出欠の対応を表示します。"),
+    ephemeral
+)]
+pub async fn attendance_config_show(ctx: Context<'_>) -> Result<(), crate::Error> {
+    let guild_id = guild_id(ctx)?;
+    let emojis = ctx.data().guild_configs
+        .lock()
+        .await
+        .get(&guild_id)
+        .cloned()
+        .unwrap_or_default()
+        .attendance_emojis();
+    ctx.say(to_mapping_text(&emojis)).await?;
+
+    Ok(())
+}
+
+/// Go back to the default emojis (✅ / ❓ / ❌)
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "reset",
+    name_localized("ja", "初期化"),
+    description_localized("ja", "既定の絵文字（✅ / ❓ / ❌）に戻します。"),
+    ephemeral
+)]
+pub async fn attendance_config_reset(ctx: Context<'_>) -> Result<(), crate::Error> {
+    let emojis = update_config(ctx, |config| config.attendance = None).await?;
+    ctx.say(format!("✅ Reset to the defaults.\n{}", to_mapping_text(&emojis))).await?;
+
+    Ok(())
+}
+
+/// Applies `update` to the server's config and saves it. Returns the resulting mapping.
+async fn update_config(
+    ctx: Context<'_>,
+    update: impl FnOnce(&mut GuildConfig),
+) -> Result<Vec<AttendanceEmoji>, crate::Error> {
+    let guild_id = guild_id(ctx)?;
+    let store = &ctx.data().guild_configs;
+
+    let mut configs = store.lock().await;
+    let config = configs.entry(guild_id).or_default();
+    update(config);
+    let emojis = config.attendance_emojis();
+    store.save(&configs)?;
+
+    Ok(emojis)
+}
+
+fn guild_id(ctx: Context<'_>) -> Result<GuildId, crate::Error> {
+    ctx.guild_id().ok_or_else(|| "This command can only be used in a server.".into())
+}
+
+fn to_mapping_text(emojis: &[AttendanceEmoji]) -> String {
+    let lines: String = AttendanceStatus::ALL
+        .iter()
+        .map(|status| {
+            let status_emojis: Vec<&str> = emojis
+                .iter()
+                .filter(|mapping| mapping.status == *status)
+                .map(|mapping| mapping.emoji.as_str())
+                .collect();
+            format!(
+                "  {}: {}\n",
+                status.label(),
+                if status_emojis.is_empty() { "-".to_string() } else { status_emojis.join(" ") }
+            )
+        })
+        .collect();
+    format!("Attendance emojis:\n{}", lines)
+}
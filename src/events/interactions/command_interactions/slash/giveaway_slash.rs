@@ -0,0 +1,185 @@
+use poise::serenity_prelude::{Mentionable, Message};
+
+use crate::services::giveaway::{
+    end_giveaway, reroll_giveaway, start_giveaway, to_giveaway_list_text, GiveawayDraw,
+    GiveawayStartParameter,
+};
+use crate::utils::parsers::parse_duration;
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /giveaway slash command
+///
+/// Only the subcommands can be run.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("giveaway_start", "giveaway_end", "giveaway_reroll", "giveaway_list"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_MESSAGES",
+    name_localized("ja", "プレゼント企画"),
+    description_localized("ja", "リアクションで参加するプレゼント企画を開催します。")
+)]
+pub async fn giveaway(_ctx: Context<'_>) -> Result<(), crate::Error> {
+    Ok(())
+}
+
+/// Start a giveaway in this channel
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "start",
+    name_localized("ja", "開始"),
+    description_localized("ja", "このチャンネルでプレゼント企画を開始します。"),
+    ephemeral
+)]
+pub async fn giveaway_start(
+    ctx: Context<'_>,
+
+    #[description = "What the winners get."]
+    #[description_localized("ja", "当選者への賞品")]
+    #[max_length = 200]
+    prize: String,
+
+    #[description = "How long the giveaway runs, e.g. 30m, 1h30m, 2d."]
+    #[description_localized("ja", "開催期間。例: 30m, 1h30m, 2d")]
+    duration: String,
+
+    #[description = "Number of winners. Default: 1."]
+    #[description_localized("ja", "当選者の人数。既定: 1")]
+    #[min = 1]
+    #[max = 100]
+    winners: Option<u32>,
+
+    #[description = "Emoji to react with to enter. Default: 🎉"]
+    #[description_localized("ja", "参加するためのリアクションの絵文字。既定: 🎉")]
+    emoji: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer_ephemeral().await?;
+
+    let duration = match parse_duration(&duration) {
+        Ok(duration) if duration > 0 => duration,
+        Ok(_) => {
+            ctx.say("⚠️ Error: The duration must be longer than zero.").await?;
+            return Ok(());
+        }
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    let parameter = GiveawayStartParameter {
+        channel_id: ctx.channel_id(),
+        guild_id: ctx.guild_id(),
+        host_id: ctx.author().id,
+        prize,
+        duration,
+        winners: winners.unwrap_or(1) as usize,
+        emoji,
+    };
+
+    match start_giveaway(ctx.http(), &ctx.data().giveaways, &parameter).await {
+        Ok(giveaway) => {
+            ctx.say(format!(
+                "🎉 Giveaway started: {}\nIt ends <t:{}:R>.",
+                giveaway.message_url(),
+                giveaway.ends_at
+            ))
+            .await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// End a giveaway now and draw its winners
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "end",
+    name_localized("ja", "終了"),
+    description_localized("ja", "プレゼント企画を今すぐ終了して当選者を抽選します。"),
+    ephemeral
+)]
+pub async fn giveaway_end(
+    ctx: Context<'_>,
+
+    #[description = "The giveaway message ID or URL."]
+    #[description_localized("ja", "プレゼント企画のメッセージのIDまたはURL")]
+    message: Message,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer_ephemeral().await?;
+
+    let result = end_giveaway(ctx.http(), &ctx.data().giveaways, ctx.guild_id(), message.id).await;
+    ctx.say(to_draw_result_text(result)).await?;
+
+    Ok(())
+}
+
+/// Draw new winners of an ended giveaway, excluding previous winners
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "reroll",
+    name_localized("ja", "再抽選"),
+    description_localized("ja", "終了したプレゼント企画を、過去の当選者を除いて再抽選します。"),
+    ephemeral
+)]
+pub async fn giveaway_reroll(
+    ctx: Context<'_>,
+
+    #[description = "The giveaway message ID or URL."]
+    #[description_localized("ja", "プレゼント企画のメッセージのIDまたはURL")]
+    message: Message,
+
+    #[description = "Number of new winners. Default: 1."]
+    #[description_localized("ja", "新しい当選者の人数。既定: 1")]
+    #[min = 1]
+    #[max = 100]
+    winners: Option<u32>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer_ephemeral().await?;
+
+    let winners = winners.unwrap_or(1) as usize;
+    let result = reroll_giveaway(ctx.http(), &ctx.data().giveaways, ctx.guild_id(), message.id, winners).await;
+    ctx.say(to_draw_result_text(result)).await?;
+
+    Ok(())
+}
+
+/// List the giveaways of this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    name_localized("ja", "一覧"),
+    description_localized("ja", "このサーバーのプレゼント企画を一覧表示します。"),
+    ephemeral
+)]
+pub async fn giveaway_list(ctx: Context<'_>) -> Result<(), crate::Error> {
+    let text = to_giveaway_list_text(&ctx.data().giveaways.lock().await, ctx.guild_id());
+    ctx.say(text).await?;
+
+    Ok(())
+}
+
+fn to_draw_result_text(result: Result<GiveawayDraw, crate::Error>) -> String {
+    match result {
+        Ok(draw) if draw.winners.is_empty() => "Drawn, but no one could win.".to_string(),
+        Ok(draw) => format!(
+            "Drawn: {}",
+            draw.winners.iter().map(|id| id.mention().to_string()).collect::<Vec<String>>().join(", ")
+        ),
+        Err(e) => format!("⚠️ Error: {}", e),
+    }
+}
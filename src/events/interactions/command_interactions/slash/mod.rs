@@ -1 +1,17 @@
-pub mod reaction_members;
\ No newline at end of file
+pub mod attendance_config_slash;
+pub mod giveaway_slash;
+pub mod reaction_attendance_slash;
+#[cfg(feature = "snapshots")]
+pub mod reaction_diff_slash;
+pub mod reaction_draw_slash;
+pub mod reaction_live_slash;
+pub mod reaction_members;
+pub mod reaction_mentions_slash;
+pub mod reaction_missing_slash;
+pub mod reaction_query_slash;
+pub mod reaction_rating_slash;
+pub mod reaction_signup_slash;
+#[cfg(feature = "snapshots")]
+pub mod reaction_snapshot_slash;
+pub mod reaction_teams_slash;
+pub mod reaction_watch_slash;
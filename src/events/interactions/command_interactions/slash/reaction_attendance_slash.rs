@@ -0,0 +1,50 @@
+use crate::services::reaction_attendance::{
+    default_attendance_emojis, process_reaction_attendance, ReactionAttendanceParameter,
+};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_attendance slash command
+#[poise::command(
+    slash_command,
+    name_localized("ja", "出欠確認"),
+    description_localized("ja", "リアクションを出欠として集計します。絵文字と出欠の対応はサーバーごとに設定できます。"),
+    ephemeral
+)]
+pub async fn reaction_attendance(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to fetch reactions from."]
+    #[description_localized("ja", "リアクションを取得するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    // サーバーの設定が無ければ既定の絵文字を使う
+    let emojis = match ctx.guild_id() {
+        Some(guild_id) => ctx.data().guild_configs
+            .lock()
+            .await
+            .get(&guild_id)
+            .map(|config| config.attendance_emojis())
+            .unwrap_or_else(default_attendance_emojis),
+        None => default_attendance_emojis(),
+    };
+
+    let parameter = ReactionAttendanceParameter { message, emojis };
+
+    match process_reaction_attendance(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
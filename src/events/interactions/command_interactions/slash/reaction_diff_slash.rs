@@ -0,0 +1,42 @@
+use crate::services::reaction_snapshot::diff::{process_reaction_diff, ReactionDiffParameter};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_diff slash command
+#[poise::command(
+    slash_command,
+    name_localized("ja", "リアクション差分"),
+    description_localized("ja", "保存した記録と比べて、リアクションを追加・削除した人を表示します。"),
+    ephemeral
+)]
+pub async fn reaction_diff(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to fetch reactions from."]
+    #[description_localized("ja", "リアクションを取得するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "The snapshot number to compare with. Default: the latest snapshot of the message."]
+    #[description_localized("ja", "比較する記録の番号。既定: メッセージの最新の記録")]
+    #[min = 1]
+    since_snapshot: Option<i64>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    let parameter = ReactionDiffParameter { message, since_snapshot };
+
+    match process_reaction_diff(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
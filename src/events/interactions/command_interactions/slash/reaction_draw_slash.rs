@@ -0,0 +1,70 @@
+use poise::serenity_prelude::CreateAllowedMentions;
+
+use crate::services::reaction_draw::{process_reaction_draw, ReactionDrawParameter};
+use crate::utils::parsers::parse_seed;
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_draw slash command
+///
+/// The result is posted publicly so that the draw can be audited.
+#[poise::command(
+    slash_command,
+    name_localized("ja", "リアクション抽選"),
+    description_localized("ja", "リアクションした人の中から当選者を抽選します。シードを指定すると再現できます。")
+)]
+pub async fn reaction_draw(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to draw reactors from."]
+    #[description_localized("ja", "抽選対象のリアクションがあるメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "Number of winners."]
+    #[description_localized("ja", "当選者の人数")]
+    #[min = 1]
+    #[max = 100]
+    winners: u32,
+
+    #[description = "Only users who reacted with this emoji are candidates. Default: any reaction."]
+    #[description_localized("ja", "この絵文字でリアクションした人のみを候補にします。既定: 全てのリアクション")]
+    emoji: Option<String>,
+
+    #[description = "Seed to reproduce a previous draw (0 to 18446744073709551615). Default: random."]
+    #[description_localized("ja", "過去の抽選を再現するシード（0〜18446744073709551615）。既定: ランダム")]
+    seed: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    // Discordの整数オプションは2^53までのため、シードは文字列で受け取る
+    let seed = match seed.as_deref().map(parse_seed).transpose() {
+        Ok(seed) => seed,
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    let parameter = ReactionDrawParameter {
+        message,
+        winners: winners as usize,
+        emoji,
+        seed,
+    };
+
+    // 作者や候補者ではなく、当選者のみに通知する
+    match process_reaction_draw(ctx, &parameter).await {
+        Ok((response, winners)) => {
+            for reply in response.into_replies() {
+                ctx.send(reply.allowed_mentions(CreateAllowedMentions::new().users(winners.clone()))).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::services::reaction_live::{
+    start_live_summary, LiveSummaryParameter, DEFAULT_LIVE_DURATION, MAX_LIVE_DURATION,
+};
+use crate::utils::parsers::parse_duration;
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_live slash command
+///
+/// The summary is posted publicly in the channel and kept up to date.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_MESSAGES",
+    name_localized("ja", "リアクション集計掲示"),
+    description_localized("ja", "リアクションの集計をこのチャンネルに投稿し、リアクションの変化に合わせて更新します。"),
+    ephemeral
+)]
+pub async fn reaction_live(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to summarize."]
+    #[description_localized("ja", "集計するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "How long the summary is updated, e.g. 12h, 3d (up to 30d). Default: 24h."]
+    #[description_localized("ja", "集計を更新する期間。例: 12h, 3d（最大30d）。既定: 24h")]
+    duration: Option<String>,
+
+    #[description = "Show the number of users of each reaction. Default: true."]
+    #[description_localized("ja", "リアクションごとの人数を表示します。既定: true")]
+    is_show_count: Option<bool>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer_ephemeral().await?;
+
+    let duration = match duration.as_deref().map(parse_duration).transpose() {
+        Ok(None) => DEFAULT_LIVE_DURATION,
+        Ok(Some(duration)) if (1..=MAX_LIVE_DURATION as u64).contains(&duration) => duration as i64,
+        Ok(Some(_)) => {
+            ctx.say("⚠️ Error: The duration must be longer than zero and at most 30 days.").await?;
+            return Ok(());
+        }
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    let parameter = LiveSummaryParameter {
+        message,
+        duration,
+        is_show_count: is_show_count.unwrap_or(true),
+    };
+
+    match start_live_summary(ctx, &parameter).await {
+        Ok(summary) => {
+            ctx.say(format!(
+                "📌 Live summary of {}: {}\nIt is updated until <t:{}:f>. Delete it to stop earlier.",
+                summary.message_url(),
+                summary.summary_url(),
+                summary.expires_at
+            ))
+            .await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
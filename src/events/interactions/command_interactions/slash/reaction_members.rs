@@ -1,5 +1,6 @@
-use crate::services::reaction_users::process_reaction_members;
-use crate::services::reaction_users::types::ReactionUsersParameter;
+use crate::services::reaction_users::filter::{ReactionFilter, RoleFilter, UserFilter};
+use crate::services::reaction_users::types::{OutputFormat, ReactionUsersParameter, UserSort};
+use crate::events::interactions::components::report_toolbar_component::send_report;
 use anyhow::Result;
 use crate::Context;
 
@@ -10,6 +11,7 @@ use crate::Context;
     description_localized("ja", "メッセージにリアクションしたユーザーを集計して表示します。"),
     ephemeral
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn reaction_members(
     ctx: Context<'_>,
 
@@ -28,6 +30,38 @@ pub async fn reaction_members(
     #[description = "True: Counts users for each reaction. False: Counts users by combining all reactions."]
     #[description_localized("ja", "True: リアクションごとにユーザーを集計します。 False: 全てのリアクションを合算してユーザーを集計します。")]
     is_reaction_grouping: Option<bool>,
+
+    #[description = "Output format. csv/tsv/json attach a file, pages adds buttons to browse large results."]
+    #[description_localized("ja", "出力形式。csv/tsv/jsonはファイルを添付し、ページ送りはボタンで大量の結果を閲覧できます。")]
+    output_format: Option<OutputFormat>,
+
+    #[description = "Only count these reactions (space/comma-separated, e.g. ✅ :tada: <:name:id>)."]
+    #[description_localized("ja", "集計対象にするリアクション（スペース・カンマ区切り 例: ✅ :tada: <:name:id>）")]
+    include_reactions: Option<String>,
+
+    #[description = "Ignore these reactions (space/comma-separated, e.g. 👀 :eyes:)."]
+    #[description_localized("ja", "集計から除外するリアクション（スペース・カンマ区切り 例: 👀 :eyes:）")]
+    exclude_reactions: Option<String>,
+
+    #[description = "Only count server members with this role."]
+    #[description_localized("ja", "このロールを持つサーバーメンバーのみを集計")]
+    include_role: Option<poise::serenity_prelude::Role>,
+
+    #[description = "Ignore server members with this role."]
+    #[description_localized("ja", "このロールを持つサーバーメンバーを集計から除外")]
+    exclude_role: Option<poise::serenity_prelude::Role>,
+
+    #[description = "Ignore reactions from bots."]
+    #[description_localized("ja", "Botのリアクションを集計から除外")]
+    exclude_bots: Option<bool>,
+
+    #[description = "Ignore your own reactions."]
+    #[description_localized("ja", "自分のリアクションを集計から除外")]
+    exclude_self: Option<bool>,
+
+    #[description = "Order of the users. Default: reaction order."]
+    #[description_localized("ja", "ユーザーの並び順。既定: リアクション順")]
+    sort: Option<UserSort>,
 ) -> Result<(), crate::Error> {
 
     // Acknowledge the interaction first
@@ -39,23 +73,26 @@ pub async fn reaction_members(
         is_reaction_grouping: is_reaction_grouping.unwrap_or(false),
         is_author_include: is_author_include.unwrap_or(false),
         is_show_count: is_show_count.unwrap_or(false),
+        output_format: output_format.unwrap_or_default(),
+        reaction_filter: ReactionFilter::parse(
+            include_reactions.as_deref(),
+            exclude_reactions.as_deref(),
+        ).await,
+        role_filter: RoleFilter {
+            include: include_role.map(|role| role.id),
+            exclude: exclude_role.map(|role| role.id),
+        },
+        user_filter: UserFilter {
+            exclude_bots: exclude_bots.unwrap_or(false),
+            exclude_user: exclude_self.unwrap_or(false).then(|| ctx.author().id),
+        },
+        sort: sort.unwrap_or_default(),
     };
 
-    // Process reactions and generate a response
-    let response = process_reaction_members(
-        ctx,
-        &parameter
-    ).await;
-
-    match response {
-        Ok(response) => {
-            // If processing was successful, send the response content
-            ctx.say(response.content).await?;
-        },
-        Err(e) => {
-            // If there was an error, send an error message
-            ctx.say(format!("⚠️ Error: {}", e)).await?;
-        }
+    // Send the report; text and pages come with the toolbar
+    if let Err(e) = send_report(ctx, parameter).await {
+        // If there was an error, send an error message
+        ctx.say(format!("⚠️ Error: {}", e)).await?;
     }
 
     Ok(())
@@ -0,0 +1,44 @@
+use crate::services::reaction_mentions::{process_reaction_mentions, ReactionMentionsParameter};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_mentions slash command
+#[poise::command(
+    slash_command,
+    name_localized("ja", "メンション確認"),
+    description_localized("ja", "メッセージでメンションされた人のうち、リアクションした人としていない人を表示します。"),
+    ephemeral
+)]
+pub async fn reaction_mentions(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to check mentions and reactions of."]
+    #[description_localized("ja", "メンションとリアクションを確認するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "Only this emoji counts as a response. Default: any reaction."]
+    #[description_localized("ja", "この絵文字のリアクションのみを回答とみなします。既定: 全てのリアクション")]
+    emoji: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    let parameter = ReactionMentionsParameter {
+        message,
+        emoji,
+    };
+
+    match process_reaction_mentions(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::services::reaction_missing::{process_reaction_missing, ReactionMissingParameter};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_missing slash command
+#[poise::command(
+    slash_command,
+    guild_only,
+    name_localized("ja", "未リアクションユーザー"),
+    description_localized("ja", "ロールやチャンネルのメンバーのうち、リアクションしていない人を表示します。"),
+    ephemeral
+)]
+pub async fn reaction_missing(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to fetch reactions from."]
+    #[description_localized("ja", "リアクションを取得するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "Members of this role are expected to react."]
+    #[description_localized("ja", "リアクションを期待するロール")]
+    role: Option<poise::serenity_prelude::Role>,
+
+    #[description = "Members who can view this channel are expected to react. Default: the message's channel."]
+    #[description_localized("ja", "このチャンネルを閲覧できるメンバーを対象にします。既定: メッセージのチャンネル")]
+    channel: Option<poise::serenity_prelude::GuildChannel>,
+
+    #[description = "Only this emoji counts as a response. Default: any reaction."]
+    #[description_localized("ja", "この絵文字のリアクションのみを回答とみなします。既定: 全てのリアクション")]
+    emoji: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    let parameter = ReactionMissingParameter {
+        message,
+        role: role.map(|role| role.id),
+        channel: channel.map(|channel| channel.id),
+        emoji,
+    };
+
+    match process_reaction_missing(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
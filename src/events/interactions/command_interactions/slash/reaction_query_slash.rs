@@ -0,0 +1,70 @@
+use poise::CreateReply;
+use poise::serenity_prelude::CreateAllowedMentions;
+
+use crate::services::reaction_query::{process_reaction_query, ReactionQueryParameter};
+use crate::services::reaction_users::filter::UserFilter;
+use crate::services::reaction_users::types::UserSort;
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_query slash command
+#[poise::command(
+    slash_command,
+    name_localized("ja", "リアクション条件検索"),
+    description_localized("ja", "リアクションの組み合わせ条件（例: ✅ & !❌ | 🎉）に一致するユーザーを表示します。"),
+    ephemeral
+)]
+pub async fn reaction_query(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to fetch reactions from."]
+    #[description_localized("ja", "リアクションを取得するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "Set expression: & (and), | (or), - (difference), ! (not), parentheses. e.g. ✅ & !❌ | 🎉"]
+    #[description_localized("ja", "条件式: &（かつ）, |（または）, -（差）, !（否定）, 括弧。例: ✅ & !❌ | 🎉")]
+    expression: String,
+
+    #[description = "Ignore reactions from bots."]
+    #[description_localized("ja", "Botのリアクションを集計から除外")]
+    exclude_bots: Option<bool>,
+
+    #[description = "Ignore your own reactions."]
+    #[description_localized("ja", "自分のリアクションを集計から除外")]
+    exclude_self: Option<bool>,
+
+    #[description = "Order of the users. Default: reaction order."]
+    #[description_localized("ja", "ユーザーの並び順。既定: リアクション順")]
+    sort: Option<UserSort>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    let parameter = ReactionQueryParameter {
+        message,
+        expression,
+        user_filter: UserFilter {
+            exclude_bots: exclude_bots.unwrap_or(false),
+            exclude_user: exclude_self.unwrap_or(false).then(|| ctx.author().id),
+        },
+        sort: sort.unwrap_or_default(),
+    };
+
+    // 式はユーザー入力のため、返信に含まれる@everyoneなどで通知しない
+    match process_reaction_query(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply.allowed_mentions(CreateAllowedMentions::new())).await?;
+            }
+        },
+        Err(e) => {
+            let reply = CreateReply::default()
+                .content(format!("⚠️ Error: {}", e))
+                .allowed_mentions(CreateAllowedMentions::new());
+            ctx.send(reply).await?;
+        }
+    }
+
+    Ok(())
+}
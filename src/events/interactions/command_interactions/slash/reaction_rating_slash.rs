@@ -0,0 +1,51 @@
+use crate::services::reaction_rating::{
+    default_score_emojis, parse_score_emojis, process_reaction_rating, ReactionRatingParameter,
+};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_rating slash command
+#[poise::command(
+    slash_command,
+    name_localized("ja", "リアクション評価"),
+    description_localized("ja", "数字などの絵文字のリアクションを点数として集計し、平均や分布を表示します。"),
+    ephemeral
+)]
+pub async fn reaction_rating(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to fetch reactions from."]
+    #[description_localized("ja", "リアクションを取得するメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "Score of each emoji, e.g. \"😡=1, 😐=3, 😍=5\". Default: 0️⃣-9️⃣ and 🔟."]
+    #[description_localized("ja", "絵文字ごとの点数。例: \"😡=1, 😐=3, 😍=5\"。既定: 0️⃣〜9️⃣と🔟")]
+    scores: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    let emojis = match scores.as_deref().map(parse_score_emojis).transpose() {
+        Ok(emojis) => emojis.unwrap_or_else(default_score_emojis),
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    let parameter = ReactionRatingParameter { message, emojis };
+
+    match process_reaction_rating(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
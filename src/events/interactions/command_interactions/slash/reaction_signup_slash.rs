@@ -0,0 +1,110 @@
+use poise::serenity_prelude::Message;
+
+use crate::services::reaction_watch::get_signup_text;
+use crate::services::reaction_watch::signup::{start_signup, SignupConfig};
+use crate::utils::message_splitter::{split_message, MESSAGE_CHARACTER_LIMIT};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_signup slash command
+///
+/// Only the subcommands can be run.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("reaction_signup_start", "reaction_signup_show"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_MESSAGES",
+    name_localized("ja", "先着受付"),
+    description_localized("ja", "リアクションした順に定員まで確定し、残りをキャンセル待ちにします。")
+)]
+pub async fn reaction_signup(_ctx: Context<'_>) -> Result<(), crate::Error> {
+    Ok(())
+}
+
+/// Make a message a first-come-first-served sign-up, or change its settings
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "start",
+    name_localized("ja", "開始"),
+    description_localized("ja", "メッセージを先着順の受付にします。受付中の場合は設定を変更します。"),
+    ephemeral
+)]
+pub async fn reaction_signup_start(
+    ctx: Context<'_>,
+
+    #[description = "The sign-up message ID or URL."]
+    #[description_localized("ja", "受付メッセージのIDまたはURL")]
+    message: Message,
+
+    #[description = "Number of confirmed spots."]
+    #[description_localized("ja", "定員")]
+    #[min = 1]
+    #[max = 1000]
+    capacity: u32,
+
+    #[description = "Only reactions with this emoji sign up. Default: any reaction."]
+    #[description_localized("ja", "この絵文字のリアクションのみを受け付けます。既定: 全てのリアクション")]
+    emoji: Option<String>,
+
+    #[description = "Mention waitlisted users when they get a spot. Default: true."]
+    #[description_localized("ja", "キャンセル待ちの人が繰り上がった時にメンションで通知します。既定: true")]
+    notify: Option<bool>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer_ephemeral().await?;
+
+    let config = SignupConfig {
+        capacity: capacity as usize,
+        emoji,
+        notify: notify.unwrap_or(true),
+    };
+
+    match start_signup(ctx, &message, config).await {
+        Ok(text) => {
+            ctx.say(text).await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the confirmed users and the waitlist of a sign-up
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "show",
+    name_localized("ja", "表示"),
+    description_localized("ja", "受付の確定者とキャンセル待ちを表示します。"),
+    ephemeral
+)]
+pub async fn reaction_signup_show(
+    ctx: Context<'_>,
+
+    #[description = "The sign-up message ID or URL."]
+    #[description_localized("ja", "受付メッセージのIDまたはURL")]
+    message: Message,
+) -> Result<(), crate::Error> {
+    let text = {
+        let watches = ctx.data().watches.lock().await;
+        watches
+            .get(&message.id)
+            .filter(|watch| watch.guild_id == ctx.guild_id())
+            .and_then(|watch| watch.signup_sheet().map(|sheet| get_signup_text(watch, &sheet)))
+    };
+
+    let Some(text) = text else {
+        ctx.say("⚠️ Error: This message is not a sign-up. Start with `/reaction_signup start`.").await?;
+        return Ok(());
+    };
+    for chunk in split_message(&text, MESSAGE_CHARACTER_LIMIT) {
+        ctx.say(chunk).await?;
+    }
+
+    Ok(())
+}
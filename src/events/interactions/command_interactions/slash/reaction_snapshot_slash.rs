@@ -0,0 +1,124 @@
+use poise::serenity_prelude::Message;
+
+use crate::services::reaction_snapshot::{
+    process_snapshot_save, to_snapshot_list_text, to_snapshot_text, SNAPSHOT_LIST_LIMIT,
+};
+use crate::utils::message_splitter::{split_message, MESSAGE_CHARACTER_LIMIT};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_snapshot slash command
+///
+/// Only the subcommands can be run.
+#[poise::command(
+    slash_command,
+    subcommands("reaction_snapshot_save", "reaction_snapshot_list", "reaction_snapshot_show"),
+    subcommand_required,
+    name_localized("ja", "リアクション記録"),
+    description_localized("ja", "リアクションした人をその時点の記録として保存・表示します。")
+)]
+pub async fn reaction_snapshot(_ctx: Context<'_>) -> Result<(), crate::Error> {
+    Ok(())
+}
+
+/// Save who reacted to a message right now
+#[poise::command(
+    slash_command,
+    rename = "save",
+    name_localized("ja", "保存"),
+    description_localized("ja", "メッセージに今リアクションしている人を記録します。"),
+    ephemeral
+)]
+pub async fn reaction_snapshot_save(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to fetch reactions from."]
+    #[description_localized("ja", "リアクションを取得するメッセージのIDまたはURL")]
+    message: Message,
+
+    #[description = "A note to find the snapshot again, e.g. \"sign-ups closed\"."]
+    #[description_localized("ja", "記録を見つけるためのメモ。例: \"締め切り時点\"")]
+    #[max_length = 100]
+    label: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    match process_snapshot_save(ctx, &message, label).await {
+        Ok(text) => {
+            ctx.say(text).await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// List the saved snapshots of this server
+#[poise::command(
+    slash_command,
+    rename = "list",
+    name_localized("ja", "一覧"),
+    description_localized("ja", "このサーバーで保存した記録を新しい順に表示します。"),
+    ephemeral
+)]
+pub async fn reaction_snapshot_list(
+    ctx: Context<'_>,
+
+    #[description = "Only list the snapshots of this message."]
+    #[description_localized("ja", "このメッセージの記録のみを表示します。")]
+    message: Option<Message>,
+) -> Result<(), crate::Error> {
+    let message_id = message.map(|message| message.id);
+
+    match ctx.data().snapshots.list(ctx.guild_id(), ctx.author().id, message_id, SNAPSHOT_LIST_LIMIT) {
+        Ok(summaries) => {
+            ctx.say(to_snapshot_list_text(&summaries)).await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show a saved snapshot
+#[poise::command(
+    slash_command,
+    rename = "show",
+    name_localized("ja", "表示"),
+    description_localized("ja", "保存した記録を表示します。"),
+    ephemeral
+)]
+pub async fn reaction_snapshot_show(
+    ctx: Context<'_>,
+
+    #[description = "The snapshot number, as shown by /reaction_snapshot list."]
+    #[description_localized("ja", "/reaction_snapshot list で表示される記録の番号")]
+    #[min = 1]
+    id: i64,
+) -> Result<(), crate::Error> {
+
+    // 他のサーバーや他人のDMの記録は見せない
+    let snapshot = match ctx.data().snapshots.get(id) {
+        Ok(Some(snapshot)) if snapshot.is_visible_to(ctx.guild_id(), ctx.author().id) => snapshot,
+        Ok(_) => {
+            ctx.say(format!("⚠️ Error: There is no snapshot #{} here.", id)).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    for chunk in split_message(&to_snapshot_text(&snapshot), MESSAGE_CHARACTER_LIMIT) {
+        ctx.say(chunk).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use poise::serenity_prelude::CreateAllowedMentions;
+
+use crate::services::reaction_teams::{process_reaction_teams, ReactionTeamsParameter};
+use crate::utils::parsers::parse_seed;
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_teams slash command
+///
+/// The teams are posted publicly so that every player can see them.
+#[poise::command(
+    slash_command,
+    name_localized("ja", "リアクションチーム分け"),
+    description_localized("ja", "リアクションした人をランダムにチーム分けします。シードを指定すると再現できます。")
+)]
+pub async fn reaction_teams(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to take players from."]
+    #[description_localized("ja", "参加者のリアクションがあるメッセージのIDまたはURL")]
+    message: poise::serenity_prelude::Message,
+
+    #[description = "Number of teams."]
+    #[description_localized("ja", "チーム数")]
+    #[min = 2]
+    #[max = 25]
+    team_count: u32,
+
+    #[description = "Only users who reacted with this emoji play. Default: any reaction."]
+    #[description_localized("ja", "この絵文字でリアクションした人のみを参加者にします。既定: 全てのリアクション")]
+    emoji: Option<String>,
+
+    #[description = "Users who reacted with this emoji lead one team each."]
+    #[description_localized("ja", "この絵文字でリアクションした人を各チームの主将にします。")]
+    captain_emoji: Option<String>,
+
+    #[description = "Keep users who reacted with the same emoji on the same team. Default: False"]
+    #[description_localized("ja", "同じ絵文字でリアクションした人を同じチームにします。既定: False")]
+    group_by_emoji: Option<bool>,
+
+    #[description = "Seed to reproduce a previous split (0 to 18446744073709551615). Default: random."]
+    #[description_localized("ja", "過去のチーム分けを再現するシード（0〜18446744073709551615）。既定: ランダム")]
+    seed: Option<String>,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer().await?;
+
+    // Discordの整数オプションは2^53までのため、シードは文字列で受け取る
+    let seed = match seed.as_deref().map(parse_seed).transpose() {
+        Ok(seed) => seed,
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    let parameter = ReactionTeamsParameter {
+        message,
+        team_count: team_count as usize,
+        emoji,
+        captain_emoji,
+        is_emoji_grouping: group_by_emoji.unwrap_or(false),
+        seed,
+    };
+
+    // 公開するチーム分けで作者や参加者全員に通知しない
+    match process_reaction_teams(ctx, &parameter).await {
+        Ok(response) => {
+            for reply in response.into_replies() {
+                ctx.send(reply.allowed_mentions(CreateAllowedMentions::new())).await?;
+            }
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use poise::serenity_prelude::Message;
+
+use crate::services::reaction_watch::{
+    get_watch_timeline_text, start_watch, stop_watch, to_watch_list_text, WatchedMessage,
+};
+use crate::utils::message_splitter::{split_message, MESSAGE_CHARACTER_LIMIT};
+use anyhow::Result;
+use crate::Context;
+
+/// Handle the /reaction_watch slash command
+///
+/// Only the subcommands can be run.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("reaction_watch_start", "reaction_watch_stop", "reaction_watch_list", "reaction_watch_show"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_MESSAGES",
+    name_localized("ja", "リアクション監視"),
+    description_localized("ja", "メッセージのリアクションを監視し、リアクションした日時と順番を記録します。")
+)]
+pub async fn reaction_watch(_ctx: Context<'_>) -> Result<(), crate::Error> {
+    Ok(())
+}
+
+/// Start recording the reactions of a message as they happen
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "start",
+    name_localized("ja", "開始"),
+    description_localized("ja", "メッセージのリアクションの記録を開始します。"),
+    ephemeral
+)]
+pub async fn reaction_watch_start(
+    ctx: Context<'_>,
+
+    #[description = "The message ID or URL to watch."]
+    #[description_localized("ja", "監視するメッセージのIDまたはURL")]
+    message: Message,
+) -> Result<(), crate::Error> {
+
+    // Acknowledge the interaction first
+    ctx.defer_ephemeral().await?;
+
+    match start_watch(ctx, &message, None).await {
+        Ok(count) => {
+            ctx.say(format!("👀 Watching {} ({} current reactions recorded).", message.id.link(message.channel_id, ctx.guild_id()), count)).await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Stop watching a message and delete its recorded reactions
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "stop",
+    name_localized("ja", "停止"),
+    description_localized("ja", "メッセージの監視を停止し、記録を削除します。"),
+    ephemeral
+)]
+pub async fn reaction_watch_stop(
+    ctx: Context<'_>,
+
+    #[description = "The watched message ID or URL."]
+    #[description_localized("ja", "監視中のメッセージのIDまたはURL")]
+    message: Message,
+) -> Result<(), crate::Error> {
+    match stop_watch(&ctx.data().watches, ctx.guild_id(), message.id).await {
+        Ok(text) => {
+            ctx.say(text).await?;
+        },
+        Err(e) => {
+            ctx.say(format!("⚠️ Error: {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// List the watched messages of this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    name_localized("ja", "一覧"),
+    description_localized("ja", "このサーバーで監視中のメッセージを一覧表示します。"),
+    ephemeral
+)]
+pub async fn reaction_watch_list(ctx: Context<'_>) -> Result<(), crate::Error> {
+    let text = {
+        let watches = ctx.data().watches.lock().await;
+        let mut watches: Vec<&WatchedMessage> = watches
+            .values()
+            .filter(|watch| watch.guild_id == ctx.guild_id())
+            .collect();
+        watches.sort_by_key(|watch| watch.started_at);
+        to_watch_list_text(&watches)
+    };
+    ctx.say(text).await?;
+
+    Ok(())
+}
+
+/// Show when each user reacted to a watched message, in order
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "show",
+    name_localized("ja", "表示"),
+    description_localized("ja", "監視中のメッセージに各ユーザーがリアクションした日時を順番に表示します。"),
+    ephemeral
+)]
+pub async fn reaction_watch_show(
+    ctx: Context<'_>,
+
+    #[description = "The watched message ID or URL."]
+    #[description_localized("ja", "監視中のメッセージのIDまたはURL")]
+    message: Message,
+) -> Result<(), crate::Error> {
+    let text = ctx
+        .data()
+        .watches
+        .lock()
+        .await
+        .get(&message.id)
+        .filter(|watch| watch.guild_id == ctx.guild_id())
+        .map(get_watch_timeline_text);
+
+    let Some(text) = text else {
+        ctx.say("⚠️ Error: This message is not watched. Start with `/reaction_watch start`.").await?;
+        return Ok(());
+    };
+    for chunk in split_message(&text, MESSAGE_CHARACTER_LIMIT) {
+        ctx.say(chunk).await?;
+    }
+
+    Ok(())
+}
@@ -1 +1,2 @@
-// Components module - placeholder for future component interactions
\ No newline at end of file
+pub mod pagination_component;
+pub mod report_toolbar_component;
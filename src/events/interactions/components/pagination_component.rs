@@ -0,0 +1,101 @@
+use poise::serenity_prelude::{ButtonStyle, CreateActionRow, CreateButton};
+
+/// Navigation buttons of a paginated reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageButton {
+    First,
+    Previous,
+    Next,
+    Last,
+}
+
+impl PageButton {
+    const ALL: [PageButton; 4] = [PageButton::First, PageButton::Previous, PageButton::Next, PageButton::Last];
+
+    /// Finds the button whose ID is `prefix` followed by its name
+    pub fn from_custom_id(custom_id: &str, prefix: &str) -> Option<Self> {
+        let suffix = custom_id.strip_prefix(prefix)?;
+        Self::ALL.into_iter().find(|button| button.suffix() == suffix)
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            PageButton::First => "first",
+            PageButton::Previous => "prev",
+            PageButton::Next => "next",
+            PageButton::Last => "last",
+        }
+    }
+
+    fn emoji(self) -> char {
+        match self {
+            PageButton::First => '⏮',
+            PageButton::Previous => '◀',
+            PageButton::Next => '▶',
+            PageButton::Last => '⏭',
+        }
+    }
+
+    /// Page shown after clicking the button on page `current` of `total`
+    pub fn target(self, current: usize, total: usize) -> usize {
+        let last = total.saturating_sub(1);
+        match self {
+            PageButton::First => 0,
+            PageButton::Previous => current.saturating_sub(1),
+            PageButton::Next => (current + 1).min(last),
+            PageButton::Last => last,
+        }
+    }
+
+    /// Buttons that would not move are disabled
+    fn is_disabled(self, current: usize, total: usize) -> bool {
+        self.target(current, total) == current
+    }
+}
+
+/// Row of First/Previous/Next/Last buttons for page `current` of `total`.
+///
+/// Button IDs are `prefix` followed by the button name; see [`PageButton::from_custom_id`].
+pub fn page_buttons(prefix: &str, current: usize, total: usize) -> CreateActionRow {
+    let buttons = PageButton::ALL
+        .into_iter()
+        .map(|button| {
+            CreateButton::new(format!("{}{}", prefix, button.suffix()))
+                .emoji(button.emoji())
+                .style(ButtonStyle::Secondary)
+                .disabled(button.is_disabled(current, total))
+        })
+        .collect();
+    CreateActionRow::Buttons(buttons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_button_target() {
+        assert_eq!(PageButton::Next.target(0, 3), 1);
+        assert_eq!(PageButton::Next.target(2, 3), 2);
+        assert_eq!(PageButton::Previous.target(0, 3), 0);
+        assert_eq!(PageButton::Previous.target(2, 3), 1);
+        assert_eq!(PageButton::First.target(2, 3), 0);
+        assert_eq!(PageButton::Last.target(0, 3), 2);
+    }
+
+    #[test]
+    fn test_page_button_from_custom_id() {
+        assert_eq!(PageButton::from_custom_id("42:page:next", "42:page:"), Some(PageButton::Next));
+        assert_eq!(PageButton::from_custom_id("42:page:last", "42:page:"), Some(PageButton::Last));
+        assert_eq!(PageButton::from_custom_id("43:page:next", "42:page:"), None);
+        assert_eq!(PageButton::from_custom_id("42:page:other", "42:page:"), None);
+    }
+
+    #[test]
+    fn test_page_button_disabled_at_edges() {
+        assert!(PageButton::First.is_disabled(0, 3));
+        assert!(PageButton::Previous.is_disabled(0, 3));
+        assert!(!PageButton::Next.is_disabled(0, 3));
+        assert!(PageButton::Last.is_disabled(2, 3));
+    }
+}
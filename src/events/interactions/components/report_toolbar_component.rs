@@ -0,0 +1,273 @@
+use std::time::Duration;
+use poise::CreateReply;
+use poise::serenity_prelude::{
+    ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateInteractionResponse,
+};
+
+use crate::events::interactions::components::pagination_component::{page_buttons, PageButton};
+use crate::services::reaction_users::renderers::{render_pages, renderer_for};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{OutputFormat, ReactionUsersParameter};
+use crate::services::reaction_users::{apply_user_options, build_reaction_report, process_reaction_members};
+use crate::Context;
+
+/// How long the buttons stay active after the last click.
+const TOOLBAR_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Buttons that change how the report is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolbarButton {
+    Grouping,
+    Count,
+    Author,
+    Refresh,
+    Csv,
+}
+
+impl ToolbarButton {
+    const ALL: [ToolbarButton; 5] = [
+        ToolbarButton::Grouping,
+        ToolbarButton::Count,
+        ToolbarButton::Author,
+        ToolbarButton::Refresh,
+        ToolbarButton::Csv,
+    ];
+
+    fn from_custom_id(custom_id: &str, prefix: &str) -> Option<Self> {
+        let suffix = custom_id.strip_prefix(prefix)?;
+        Self::ALL.into_iter().find(|button| button.suffix() == suffix)
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            ToolbarButton::Grouping => "grouping",
+            ToolbarButton::Count => "count",
+            ToolbarButton::Author => "author",
+            ToolbarButton::Refresh => "refresh",
+            ToolbarButton::Csv => "csv",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ToolbarButton::Grouping => "Group by reaction",
+            ToolbarButton::Count => "Counts",
+            ToolbarButton::Author => "Author",
+            ToolbarButton::Refresh => "Refresh",
+            ToolbarButton::Csv => "CSV",
+        }
+    }
+
+    /// Whether the option is turned on. `None` for buttons that are not toggles.
+    fn is_on(self, parameter: &ReactionUsersParameter) -> Option<bool> {
+        match self {
+            ToolbarButton::Grouping => Some(parameter.is_reaction_grouping),
+            ToolbarButton::Count => Some(parameter.is_show_count),
+            ToolbarButton::Author => Some(parameter.is_author_include),
+            ToolbarButton::Refresh | ToolbarButton::Csv => None,
+        }
+    }
+
+    /// Counts are only shown when grouping by reaction
+    fn is_disabled(self, parameter: &ReactionUsersParameter) -> bool {
+        self == ToolbarButton::Count && !parameter.is_reaction_grouping
+    }
+
+    /// Flips the option of a toggle button. Returns `false` for the other buttons.
+    fn toggle(self, parameter: &mut ReactionUsersParameter) -> bool {
+        let flag = match self {
+            ToolbarButton::Grouping => &mut parameter.is_reaction_grouping,
+            ToolbarButton::Count => &mut parameter.is_show_count,
+            ToolbarButton::Author => &mut parameter.is_author_include,
+            ToolbarButton::Refresh | ToolbarButton::Csv => return false,
+        };
+        *flag = !*flag;
+        true
+    }
+}
+
+/// Sends the report in the requested output format.
+///
+/// Text and pages are sent with the toolbar (see [`send_report_with_toolbar`]);
+/// files and embeds are sent as plain replies.
+pub async fn send_report(ctx: Context<'_>, parameter: ReactionUsersParameter) -> Result<(), crate::Error> {
+    if matches!(parameter.output_format, OutputFormat::Text | OutputFormat::Paginated) {
+        return send_report_with_toolbar(ctx, parameter).await;
+    }
+
+    let response = process_reaction_members(ctx, &parameter).await?;
+    for reply in response.into_replies() {
+        ctx.send(reply).await?;
+    }
+    Ok(())
+}
+
+/// Fetches the report and sends it as a single reply with a toolbar underneath.
+///
+/// The toolbar toggles `is_reaction_grouping`, `is_show_count` and `is_author_include`
+/// and re-renders the stored report in place; Refresh fetches the message and its
+/// reactions again, and CSV sends the stored report as a CSV file in a new reply.
+/// Paginated reports, and text reports that outgrow one message after a toggle or refresh,
+/// also get page buttons. A text report that is already too long for one message is sent
+/// without the toolbar instead, split across messages or attached as a file
+/// (see [`ReactionUsersResponse::into_replies`](crate::services::reaction_users::types::ReactionUsersResponse::into_replies)).
+/// The buttons are removed after [`TOOLBAR_TIMEOUT`] without clicks.
+///
+/// # Errors
+/// Returns an error when the first report cannot be built or a reply cannot be sent.
+/// A failed refresh keeps the previous report and shows the error in the reply.
+pub async fn send_report_with_toolbar(
+    ctx: Context<'_>,
+    mut parameter: ReactionUsersParameter,
+) -> Result<(), crate::Error> {
+    let mut report = build_reaction_report(ctx, &parameter).await?;
+
+    if parameter.output_format == OutputFormat::Text {
+        let response = renderer_for(OutputFormat::Text).render(&report, &parameter)?;
+        if !response.fits_in_one_message() {
+            // 1通に収まらないテキストは、分割またはファイル添付で送る
+            for reply in response.into_replies() {
+                ctx.send(reply).await?;
+            }
+            return Ok(());
+        }
+    }
+
+    let mut pages = render_pages(&report, &parameter)?;
+    let mut current = 0;
+
+    // ボタンのIDはコマンド実行ごとに一意にする
+    let ctx_id = ctx.id();
+    let toolbar_prefix = format!("{}:toolbar:", ctx_id);
+    let page_prefix = format!("{}:page:", ctx_id);
+
+    let view = |pages: &[String], current: usize, parameter: &ReactionUsersParameter, notice: &str| {
+        let mut components = vec![toolbar_buttons(&toolbar_prefix, parameter)];
+        if pages.len() > 1 {
+            components.push(page_buttons(&page_prefix, current, pages.len()));
+        }
+        CreateReply::default()
+            .content(format!("{}{}", pages[current], notice))
+            .components(components)
+    };
+
+    let handle = ctx.send(view(&pages, current, &parameter, "")).await?;
+
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&format!("{}:", ctx_id)))
+        .timeout(TOOLBAR_TIMEOUT)
+        .await
+    {
+        // 再取得に時間がかかる場合があるため、先に応答しておく
+        press.create_response(ctx.serenity_context(), CreateInteractionResponse::Acknowledge).await?;
+
+        let custom_id = press.data.custom_id.as_str();
+        let mut notice = String::new();
+
+        if let Some(button) = PageButton::from_custom_id(custom_id, &page_prefix) {
+            current = button.target(current, pages.len());
+        } else if let Some(button) = ToolbarButton::from_custom_id(custom_id, &toolbar_prefix) {
+            if button == ToolbarButton::Csv {
+                // 表示は変えずに、ファイルを別の返信で送る
+                let response = renderer_for(OutputFormat::Csv).render(&report, &parameter)?;
+                for reply in response.into_replies() {
+                    ctx.send(reply).await?;
+                }
+                continue;
+            }
+            if button.toggle(&mut parameter) {
+                apply_user_options(&mut report, &parameter);
+            } else {
+                match refresh_report(ctx, &mut parameter).await {
+                    Ok(refreshed) => report = refreshed,
+                    Err(e) => notice = format!("\n⚠️ Failed to refresh: {}", e),
+                }
+            }
+            pages = render_pages(&report, &parameter)?;
+            current = current.min(pages.len().saturating_sub(1));
+        } else {
+            continue;
+        }
+
+        handle.edit(ctx, view(&pages, current, &parameter, &notice)).await?;
+    }
+
+    // 操作が無くなったらボタンを外す
+    handle
+        .edit(ctx, CreateReply::default().content(&pages[current]).components(Vec::new()))
+        .await?;
+
+    Ok(())
+}
+
+/// Fetches the message again, so that reactions added since the last fetch are included.
+async fn refresh_report(
+    ctx: Context<'_>,
+    parameter: &mut ReactionUsersParameter,
+) -> Result<ReactionReport, crate::Error> {
+    let message = parameter.message.channel_id.message(ctx, parameter.message.id).await?;
+    parameter.message = message;
+    build_reaction_report(ctx, parameter).await
+}
+
+fn toolbar_buttons(prefix: &str, parameter: &ReactionUsersParameter) -> CreateActionRow {
+    let buttons = ToolbarButton::ALL
+        .into_iter()
+        .map(|button| {
+            let style = match button.is_on(parameter) {
+                Some(true) => ButtonStyle::Success,
+                Some(false) => ButtonStyle::Secondary,
+                None => ButtonStyle::Primary,
+            };
+            let button_builder = CreateButton::new(format!("{}{}", prefix, button.suffix()))
+                .label(button.label())
+                .style(style)
+                .disabled(button.is_disabled(parameter));
+            match button {
+                ToolbarButton::Refresh => button_builder.emoji('🔄'),
+                ToolbarButton::Csv => button_builder.emoji('📎'),
+                _ => button_builder,
+            }
+        })
+        .collect();
+    CreateActionRow::Buttons(buttons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::types::tests::parameter;
+
+    #[test]
+    fn test_toolbar_toggles() {
+        let mut parameter = parameter();
+
+        assert!(ToolbarButton::Grouping.toggle(&mut parameter));
+        assert!(parameter.is_reaction_grouping);
+        assert!(ToolbarButton::Author.toggle(&mut parameter));
+        assert!(parameter.is_author_include);
+        assert!(ToolbarButton::Author.toggle(&mut parameter));
+        assert!(!parameter.is_author_include);
+        assert!(!ToolbarButton::Refresh.toggle(&mut parameter));
+        assert!(!ToolbarButton::Csv.toggle(&mut parameter));
+    }
+
+    #[test]
+    fn test_count_disabled_without_grouping() {
+        let mut parameter = parameter();
+        assert!(ToolbarButton::Count.is_disabled(&parameter));
+
+        parameter.is_reaction_grouping = true;
+        assert!(!ToolbarButton::Count.is_disabled(&parameter));
+        assert_eq!(ToolbarButton::Count.is_on(&parameter), Some(false));
+    }
+
+    #[test]
+    fn test_toolbar_button_from_custom_id() {
+        assert_eq!(
+            ToolbarButton::from_custom_id("7:toolbar:refresh", "7:toolbar:"),
+            Some(ToolbarButton::Refresh)
+        );
+        assert_eq!(ToolbarButton::from_custom_id("7:page:next", "7:toolbar:"), None);
+    }
+}
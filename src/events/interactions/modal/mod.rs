@@ -0,0 +1 @@
+pub mod reaction_report_modal;
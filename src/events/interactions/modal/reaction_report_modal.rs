@@ -0,0 +1,76 @@
+use poise::serenity_prelude::Message;
+
+use crate::services::reaction_users::filter::{ReactionFilter, RoleFilter, UserFilter};
+use crate::services::reaction_users::report_options::{
+    parse_output_options, parse_report_flags, resolve_role_filter,
+};
+use crate::services::reaction_users::types::ReactionUsersParameter;
+use crate::Context;
+
+/// Options of the "Reaction report…" context menu.
+///
+/// Discord limits a modal to five text inputs, so the choices and flags of the
+/// slash command are typed as keywords.
+#[derive(Debug, poise::Modal)]
+#[name = "Reaction report"]
+pub struct ReactionReportModal {
+    #[name = "Only these reactions"]
+    #[placeholder = "✅ :tada: <:name:id>"]
+    pub include_reactions: Option<String>,
+
+    #[name = "Ignore these reactions"]
+    #[placeholder = "👀 :eyes:"]
+    pub exclude_reactions: Option<String>,
+
+    #[name = "Roles (prefix with - to exclude)"]
+    #[placeholder = "staff, -guest"]
+    pub roles: Option<String>,
+
+    #[name = "Output format and sort"]
+    #[placeholder = "text, reaction order"]
+    pub output: Option<String>,
+
+    #[name = "Options"]
+    #[placeholder = "grouping, count, author, no-bots, no-self"]
+    pub options: Option<String>,
+}
+
+impl ReactionReportModal {
+    /// Builds the report parameter for `message` from the submitted inputs.
+    ///
+    /// # Errors
+    /// Returns an error describing the first input that cannot be parsed.
+    pub async fn to_parameter(
+        &self,
+        ctx: Context<'_>,
+        message: Message,
+    ) -> Result<ReactionUsersParameter, crate::Error> {
+        let flags = parse_report_flags(self.options.as_deref().unwrap_or_default())?;
+        let (output_format, sort) = parse_output_options(self.output.as_deref().unwrap_or_default())?;
+
+        // HTTPで取得したメッセージにはguild_idが含まれないため、コマンド実行元のサーバーを使う
+        let role_filter = match (self.roles.as_deref(), message.guild_id.or(ctx.guild_id())) {
+            (Some(roles), Some(guild_id)) => resolve_role_filter(ctx, guild_id, roles).await?,
+            (Some(_), None) => return Err("Role filters can only be used on messages in a server.".into()),
+            (None, _) => RoleFilter::default(),
+        };
+
+        Ok(ReactionUsersParameter {
+            is_reaction_grouping: flags.is_reaction_grouping,
+            is_author_include: flags.is_author_include,
+            is_show_count: flags.is_show_count,
+            output_format,
+            reaction_filter: ReactionFilter::parse(
+                self.include_reactions.as_deref(),
+                self.exclude_reactions.as_deref(),
+            ).await,
+            role_filter,
+            user_filter: UserFilter {
+                exclude_bots: flags.exclude_bots,
+                exclude_user: flags.exclude_self.then(|| ctx.author().id),
+            },
+            sort,
+            message,
+        })
+    }
+}
@@ -1 +1,2 @@
-pub mod interactions;
+pub mod event_handler;
+pub mod interactions;
\ No newline at end of file
@@ -1,14 +1,42 @@
 use std::env;
+use std::sync::Arc;
 use dotenv::dotenv;
 use poise::serenity_prelude::GatewayIntents;
 
+use crate::services::giveaway::scheduler::run_giveaway_scheduler;
+use crate::services::giveaway::GiveawayStore;
+use crate::services::guild_config::GuildConfigStore;
+use crate::services::reaction_live::updater::run_live_summary_updater;
+use crate::services::reaction_live::{LiveSummaryStore, LiveUpdateQueue};
+use crate::services::reaction_watch::WatchStore;
+#[cfg(feature = "snapshots")]
+use crate::services::reaction_snapshot::store::SnapshotStore;
+
 mod events;
 mod services;
 mod utils;
 
-pub(crate) type Data = ();
+/// State shared by every command
+#[derive(Debug)]
+pub(crate) struct Data {
+    /// Giveaways, persisted so that their end times survive restarts
+    pub giveaways: Arc<GiveawayStore>,
+    /// Per-server settings
+    pub guild_configs: GuildConfigStore,
+    /// Watched messages and their reaction events
+    pub watches: WatchStore,
+    /// Self-updating summary messages
+    pub live_summaries: Arc<LiveSummaryStore>,
+    /// Summary edits waiting for their debounce
+    pub live_updates: Arc<LiveUpdateQueue>,
+    /// Saved reaction snapshots
+    #[cfg(feature = "snapshots")]
+    pub snapshots: Arc<SnapshotStore>,
+}
+
 pub(crate) type Error = Box<dyn std::error::Error + Send + Sync>;
 pub(crate) type Context<'a> = poise::Context<'a, Data, Error>;
+pub(crate) type ApplicationContext<'a> = poise::ApplicationContext<'a, Data, Error>;
 
 
 #[tokio::main]
@@ -24,20 +52,61 @@ async fn main() {
         .expect("Expected DISCORD_TOKEN in environment");
 
     // Set gateway intents
-    let intents = GatewayIntents::GUILD_MESSAGES
+    let mut intents = GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::GUILD_MESSAGE_REACTIONS
         | GatewayIntents::MESSAGE_CONTENT;
 
+    // Privileged intent, required to enumerate server members
+    if utils::config::is_guild_members_intent_enabled() {
+        intents |= GatewayIntents::GUILDS | GatewayIntents::GUILD_MEMBERS;
+    }
+
+    // Load the persisted state
+    let giveaways = GiveawayStore::load(utils::config::data_dir().join("giveaways.json"))
+        .expect("Failed to load giveaways");
+    let giveaways = Arc::new(giveaways);
+    let guild_configs = GuildConfigStore::load(utils::config::data_dir().join("guild_config.json"))
+        .expect("Failed to load server settings");
+    let watches = WatchStore::load(utils::config::data_dir().join("reaction_watches.json"))
+        .expect("Failed to load watched messages");
+    let live_summaries = LiveSummaryStore::load(utils::config::data_dir().join("live_summaries.json"))
+        .expect("Failed to load live summaries");
+    let live_summaries = Arc::new(live_summaries);
+    let live_updates = Arc::new(LiveUpdateQueue::default());
+    #[cfg(feature = "snapshots")]
+    let snapshots = SnapshotStore::open(&utils::config::data_dir().join("snapshots.sqlite3"))
+        .expect("Failed to open the snapshot database");
+    #[cfg(feature = "snapshots")]
+    let snapshots = Arc::new(snapshots);
+
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
             commands: commands(),
+            event_handler: |ctx, event, framework, data| {
+                Box::pin(events::event_handler::event_handler(ctx, event, framework, data))
+            },
             ..Default::default()
         })
         .setup(|_ctx, _ready, _framework| {
             Box::pin(async move {
                 // Any setup code can go here
                 poise::builtins::register_globally(_ctx, &_framework.options().commands).await?;
-                Ok(())
+
+                // Giveaways are ended in the background, including those that ended while offline
+                tokio::spawn(run_giveaway_scheduler(_ctx.http.clone(), giveaways.clone()));
+
+                // Live summaries are edited in the background, debounced
+                tokio::spawn(run_live_summary_updater(_ctx.http.clone(), live_summaries.clone(), live_updates.clone()));
+
+                Ok(Data {
+                    giveaways,
+                    guild_configs,
+                    watches,
+                    live_summaries,
+                    live_updates,
+                    #[cfg(feature = "snapshots")]
+                    snapshots,
+                })
             })
         })
         .build();
@@ -51,11 +120,33 @@ async fn main() {
 }
 
 fn commands() -> Vec<poise::Command<Data, Error>> {
-    vec![
+    #[allow(unused_mut)]
+    let mut commands = vec![
         events::interactions::command_interactions::slash::reaction_members::reaction_members(),
+        events::interactions::command_interactions::slash::reaction_query_slash::reaction_query(),
+        events::interactions::command_interactions::slash::reaction_missing_slash::reaction_missing(),
+        events::interactions::command_interactions::slash::reaction_mentions_slash::reaction_mentions(),
+        events::interactions::command_interactions::slash::reaction_draw_slash::reaction_draw(),
+        events::interactions::command_interactions::slash::reaction_teams_slash::reaction_teams(),
+        events::interactions::command_interactions::slash::reaction_attendance_slash::reaction_attendance(),
+        events::interactions::command_interactions::slash::attendance_config_slash::attendance_config(),
+        events::interactions::command_interactions::slash::reaction_rating_slash::reaction_rating(),
+        events::interactions::command_interactions::slash::reaction_watch_slash::reaction_watch(),
+        events::interactions::command_interactions::slash::reaction_signup_slash::reaction_signup(),
+        events::interactions::command_interactions::slash::reaction_live_slash::reaction_live(),
+        events::interactions::command_interactions::slash::giveaway_slash::giveaway(),
         events::interactions::command_interactions::contextmenu::reaction_users_context_menu::get_reaction_members(),
         events::interactions::command_interactions::contextmenu::reaction_grouping_users_context_menu::get_reaction_grouping_members(),
-    ]
+        events::interactions::command_interactions::contextmenu::reaction_report_context_menu::reaction_report(),
+    ];
+
+    #[cfg(feature = "snapshots")]
+    commands.extend([
+        events::interactions::command_interactions::slash::reaction_snapshot_slash::reaction_snapshot(),
+        events::interactions::command_interactions::slash::reaction_diff_slash::reaction_diff(),
+    ]);
+
+    commands
 }
 
 #[allow(dead_code)]
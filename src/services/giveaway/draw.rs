@@ -0,0 +1,60 @@
+use poise::serenity_prelude::{Http, Message, User, UserId};
+
+use crate::services::giveaway::{Giveaway, GiveawayDraw};
+use crate::services::reaction_draw::draw::{candidate_hash, candidate_list_text, draw_winners};
+use crate::services::reaction_users::filter::{ReactionMatcher, UserFilter};
+use crate::services::reaction_users::utils::fetch_reaction_users;
+
+/// Fetches the entrants from the giveaway `message` and draws `winners` of them with a random seed.
+///
+/// Returns the draw and the candidate list, to be attached to the announcement.
+pub async fn draw_giveaway(
+    http: &Http,
+    giveaway: &Giveaway,
+    message: &Message,
+    winners: usize,
+) -> Result<(GiveawayDraw, String), crate::Error> {
+    // 参加用の絵文字のリアクションだけを見る
+    let matcher = ReactionMatcher::parse(&giveaway.emoji.to_string());
+    let users = match message.reactions.iter().find(|reaction| matcher.is_match(&reaction.reaction_type)) {
+        Some(reaction) => fetch_reaction_users(http, message, reaction).await?,
+        None => Vec::new(),
+    };
+
+    let candidates = to_candidates(&users, giveaway.host_id, &giveaway.previous_winners());
+    let seed = rand::random();
+    let draw = GiveawayDraw {
+        seed,
+        candidate_hash: candidate_hash(&candidates),
+        candidate_count: candidates.len(),
+        winners: draw_winners(&candidates, winners, seed),
+    };
+    Ok((draw, candidate_list_text(&candidates)))
+}
+
+/// Entrants who can win: no bots, not the host and no previous winner.
+fn to_candidates(users: &[User], host_id: UserId, previous_winners: &[UserId]) -> Vec<UserId> {
+    let user_filter = UserFilter { exclude_bots: true, exclude_user: Some(host_id) };
+    users
+        .iter()
+        .filter(|user| user_filter.is_match(user) && !previous_winners.contains(&user.id))
+        .map(|user| user.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::user;
+
+    #[test]
+    fn test_candidates_exclude_bots_host_and_previous_winners() {
+        let mut bot = user(2, "bot");
+        bot.bot = true;
+        let users = vec![user(1, "host"), bot, user(3, "a"), user(4, "b"), user(5, "c")];
+
+        let candidates = to_candidates(&users, UserId::new(1), &[UserId::new(4)]);
+
+        assert_eq!(candidates, vec![UserId::new(3), UserId::new(5)]);
+    }
+}
@@ -0,0 +1,198 @@
+use poise::serenity_prelude::{EditMessage, Error as SerenityError, GuildId, Http, MessageId, Timestamp};
+
+use crate::services::giveaway::draw::draw_giveaway;
+use crate::services::giveaway::render::{announce_draw, giveaway_embed};
+use crate::services::giveaway::{Giveaway, GiveawayDraw, GiveawayStatus, GiveawayStore};
+
+/// Ends a running giveaway of `guild_id`: draws the winners, saves them and announces them.
+///
+/// Called by `/giveaway end` and by the scheduler when the end time has passed.
+/// The store is not locked while Discord is called.
+///
+/// # Errors
+/// Returns an error when the giveaway is unknown, belongs to another server, is already ended
+/// or is being drawn, or when the draw or the announcement fails.
+/// A giveaway whose message or channel is gone or can no longer be accessed is marked as ended
+/// without an announcement. Other failures leave it running, so that the scheduler retries it;
+/// a draw that was already saved is announced again instead of being redrawn.
+pub async fn end_giveaway(
+    http: &Http,
+    store: &GiveawayStore,
+    guild_id: Option<GuildId>,
+    message_id: MessageId,
+) -> Result<GiveawayDraw, crate::Error> {
+    let giveaway = claim_giveaway(store, guild_id, message_id, GiveawayStatus::Running).await?;
+    let now = Timestamp::now().unix_timestamp();
+    let end = |giveaway: &mut Giveaway| {
+        giveaway.status = GiveawayStatus::Ended;
+        giveaway.ends_at = giveaway.ends_at.min(now);
+        giveaway.pending_candidate_list = None;
+    };
+
+    match announce_end(http, store, &giveaway).await {
+        Ok(draw) => {
+            finish_draw(store, message_id, end).await?;
+
+            let mut ended = giveaway.clone();
+            end(&mut ended);
+            ended.draws = vec![draw.clone()];
+            // 発表済みのため、埋め込みの更新に失敗しても結果は保存したままにする
+            if let Err(e) = ended
+                .channel_id
+                .edit_message(http, ended.message_id, EditMessage::new().embed(giveaway_embed(&ended)))
+                .await
+            {
+                log::error!("Failed to update giveaway embed {}: {}", message_id, e);
+            }
+            Ok(draw)
+        }
+        Err(e) if is_unreachable(&e) => {
+            // 再試行しても発表できないため終了とする
+            finish_draw(store, message_id, end).await?;
+            Err(format!("The giveaway message can no longer be reached, so it was ended without an announcement: {}", e).into())
+        }
+        Err(e) => {
+            finish_draw(store, message_id, |_| {}).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Draws `winners` new winners of an ended giveaway of `guild_id`, excluding everyone who already won it.
+///
+/// # Errors
+/// Returns an error when the giveaway is unknown, belongs to another server, is still running
+/// or is being drawn, or when the draw or the announcement fails.
+pub async fn reroll_giveaway(
+    http: &Http,
+    store: &GiveawayStore,
+    guild_id: Option<GuildId>,
+    message_id: MessageId,
+    winners: usize,
+) -> Result<GiveawayDraw, crate::Error> {
+    let giveaway = claim_giveaway(store, guild_id, message_id, GiveawayStatus::Ended).await?;
+
+    let result = async {
+        let message = giveaway.channel_id.message(http, giveaway.message_id).await?;
+        let (draw, candidate_list) = draw_giveaway(http, &giveaway, &message, winners).await?;
+        announce_draw(http, &giveaway, &draw, candidate_list, true).await?;
+        Ok::<GiveawayDraw, crate::Error>(draw)
+    }
+    .await;
+
+    match result {
+        Ok(draw) => {
+            finish_draw(store, message_id, |giveaway| giveaway.draws.push(draw.clone())).await?;
+            Ok(draw)
+        }
+        Err(e) => {
+            finish_draw(store, message_id, |_| {}).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Draws the end of `giveaway`, saves the draw and announces it.
+///
+/// A draw saved by an earlier, failed attempt is announced as it is.
+async fn announce_end(
+    http: &Http,
+    store: &GiveawayStore,
+    giveaway: &Giveaway,
+) -> Result<GiveawayDraw, crate::Error> {
+    let (draw, candidate_list) = match giveaway.pending_draw() {
+        Some((draw, candidate_list)) => (draw.clone(), candidate_list.to_string()),
+        None => {
+            let message = giveaway.channel_id.message(http, giveaway.message_id).await?;
+            let (draw, candidate_list) = draw_giveaway(http, giveaway, &message, giveaway.winners).await?;
+
+            // 発表に失敗しても同じ結果を発表し直せるよう、先に保存する
+            let mut giveaways = store.lock().await;
+            if let Some(saved) = giveaways.iter_mut().find(|saved| saved.message_id == giveaway.message_id) {
+                saved.draws.push(draw.clone());
+                saved.pending_candidate_list = Some(candidate_list.clone());
+            }
+            store.save(&giveaways)?;
+            (draw, candidate_list)
+        }
+    };
+
+    announce_draw(http, giveaway, &draw, candidate_list, false).await?;
+    Ok(draw)
+}
+
+/// Marks the giveaway as being drawn and returns a copy of it.
+async fn claim_giveaway(
+    store: &GiveawayStore,
+    guild_id: Option<GuildId>,
+    message_id: MessageId,
+    status: GiveawayStatus,
+) -> Result<Giveaway, crate::Error> {
+    let mut giveaways = store.lock().await;
+    let giveaway = giveaways
+        .iter_mut()
+        .find(|giveaway| giveaway.message_id == message_id && giveaway.guild_id == guild_id)
+        .ok_or("This message is not a giveaway of this server.")?;
+
+    match (status, giveaway.status) {
+        (GiveawayStatus::Running, GiveawayStatus::Ended) => {
+            return Err("This giveaway has already ended. Use `/giveaway reroll` to draw again.".into());
+        }
+        (GiveawayStatus::Ended, GiveawayStatus::Running) => {
+            return Err("This giveaway is still running. Use `/giveaway end` to end it first.".into());
+        }
+        _ => {}
+    }
+    if giveaway.is_drawing {
+        return Err("This giveaway is being drawn right now.".into());
+    }
+
+    giveaway.is_drawing = true;
+    Ok(giveaway.clone())
+}
+
+/// Applies the result of a draw and releases the claim of [`claim_giveaway`].
+async fn finish_draw(
+    store: &GiveawayStore,
+    message_id: MessageId,
+    update: impl FnOnce(&mut Giveaway),
+) -> Result<(), crate::Error> {
+    let mut giveaways = store.lock().await;
+    if let Some(giveaway) = giveaways.iter_mut().find(|giveaway| giveaway.message_id == message_id) {
+        update(giveaway);
+        giveaway.is_drawing = false;
+    }
+    store.save(&giveaways)?;
+    Ok(())
+}
+
+/// Whether Discord answered 403 or 404, i.e. the message or its channel no longer exists
+/// or the bot lost access to it
+fn is_unreachable(error: &crate::Error) -> bool {
+    match error.downcast_ref::<SerenityError>() {
+        Some(SerenityError::Http(http_error)) => {
+            http_error.status_code().is_some_and(|status| matches!(status.as_u16(), 403 | 404))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::giveaway::tests::giveaway;
+
+    #[tokio::test]
+    async fn test_claim_giveaway() {
+        // ファイルには書き込まない
+        let store = GiveawayStore::load(std::env::temp_dir().join("giveaway_claim_test_unused.json")).unwrap();
+        store.lock().await.push(giveaway(1, 100));
+        let guild_id = Some(GuildId::new(100));
+        let message_id = MessageId::new(1);
+
+        assert!(claim_giveaway(&store, Some(GuildId::new(999)), message_id, GiveawayStatus::Running).await.is_err());
+        assert!(claim_giveaway(&store, guild_id, message_id, GiveawayStatus::Ended).await.is_err());
+        assert!(claim_giveaway(&store, guild_id, message_id, GiveawayStatus::Running).await.is_ok());
+        assert!(claim_giveaway(&store, guild_id, message_id, GiveawayStatus::Running).await.is_err());
+    }
+}
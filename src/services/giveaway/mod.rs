@@ -0,0 +1,227 @@
+use poise::serenity_prelude::{
+    ChannelId, CreateAllowedMentions, CreateMessage, GuildId, Http, MessageId, ReactionType, Timestamp, UserId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::services::giveaway::render::running_giveaway_embed;
+use crate::services::reaction_users::filter::ReactionMatcher;
+use crate::utils::json_store::JsonStore;
+
+pub mod draw;
+pub mod end;
+pub mod render;
+pub mod scheduler;
+
+pub use end::{end_giveaway, reroll_giveaway};
+pub use render::to_giveaway_list_text;
+
+/// Emoji used to enter a giveaway when none is given.
+pub const DEFAULT_GIVEAWAY_EMOJI: &str = "🎉";
+
+/// Giveaways, saved so that their end times survive restarts
+pub type GiveawayStore = JsonStore<Vec<Giveaway>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GiveawayStatus {
+    Running,
+    Ended,
+}
+
+/// One draw of a giveaway: the end itself or a reroll.
+///
+/// The seed and the candidate hash are kept so that the draw can be checked
+/// the same way as `/reaction_draw`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiveawayDraw {
+    pub seed: u64,
+    pub candidate_hash: String,
+    pub candidate_count: usize,
+    pub winners: Vec<UserId>,
+}
+
+/// A giveaway posted by the bot. Entrants react to the message with `emoji`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Giveaway {
+    pub message_id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub host_id: UserId,
+    pub prize: String,
+    pub emoji: ReactionType,
+    /// Number of winners drawn when the giveaway ends
+    pub winners: usize,
+    /// End time, in seconds since the Unix epoch. Ending early moves it to the actual end.
+    pub ends_at: i64,
+    pub status: GiveawayStatus,
+    /// Draws in order; the first one is the end of the giveaway, the others are rerolls
+    pub draws: Vec<GiveawayDraw>,
+    /// Candidate list of the end draw while it is saved but not announced yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_candidate_list: Option<String>,
+    /// Whether a draw is in progress, so that the scheduler and a command never draw twice.
+    /// Not saved: a draw interrupted by a restart is simply retried.
+    #[serde(skip)]
+    pub is_drawing: bool,
+}
+
+impl Giveaway {
+    /// Whether the giveaway is still running and its end time has passed
+    pub fn is_due(&self, now: i64) -> bool {
+        self.status == GiveawayStatus::Running && self.ends_at <= now
+    }
+
+    /// The end draw and its candidate list, when they were saved but not announced yet
+    pub fn pending_draw(&self) -> Option<(&GiveawayDraw, &str)> {
+        self.draws.first().zip(self.pending_candidate_list.as_deref())
+    }
+
+    /// Everyone who has won this giveaway, in any draw
+    pub fn previous_winners(&self) -> Vec<UserId> {
+        self.draws.iter().flat_map(|draw| draw.winners.iter().copied()).collect()
+    }
+
+    pub fn message_url(&self) -> String {
+        format!(
+            "https://discord.com/channels/{}/{}/{}",
+            self.guild_id.map(|id| id.to_string()).unwrap_or_else(|| "@me".to_string()),
+            self.channel_id,
+            self.message_id,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct GiveawayStartParameter {
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub host_id: UserId,
+    pub prize: String,
+    /// Seconds until the giveaway ends
+    pub duration: u64,
+    pub winners: usize,
+    /// Emoji to enter with, as typed by the host
+    pub emoji: Option<String>,
+}
+
+/// Posts the giveaway message, adds the entry reaction and stores the giveaway.
+///
+/// # Errors
+/// Returns an error for an unusable emoji, or when the message cannot be posted or reacted to.
+pub async fn start_giveaway(
+    http: &Http,
+    store: &GiveawayStore,
+    parameter: &GiveawayStartParameter,
+) -> Result<Giveaway, crate::Error> {
+    let emoji = parse_reaction_type(parameter.emoji.as_deref().unwrap_or(DEFAULT_GIVEAWAY_EMOJI))?;
+    let duration = i64::try_from(parameter.duration).map_err(|_| "The duration is too long.")?;
+    let ends_at = Timestamp::now().unix_timestamp().saturating_add(duration);
+
+    let embed = running_giveaway_embed(&parameter.prize, &emoji, ends_at, parameter.winners, parameter.host_id);
+    // 賞品に書かれたメンションで通知しない
+    let message = parameter
+        .channel_id
+        .send_message(http, CreateMessage::new().embed(embed).allowed_mentions(CreateAllowedMentions::new()))
+        .await?;
+
+    // 絵文字が使えない場合は投稿を取り消す
+    if let Err(e) = message.react(http, emoji.clone()).await {
+        let _ = message.delete(http).await;
+        return Err(format!("Failed to react with {}: {}", emoji, e).into());
+    }
+
+    let giveaway = Giveaway {
+        message_id: message.id,
+        channel_id: parameter.channel_id,
+        guild_id: parameter.guild_id,
+        host_id: parameter.host_id,
+        prize: parameter.prize.clone(),
+        emoji,
+        winners: parameter.winners,
+        ends_at,
+        status: GiveawayStatus::Running,
+        draws: Vec::new(),
+        pending_candidate_list: None,
+        is_drawing: false,
+    };
+
+    let mut giveaways = store.lock().await;
+    giveaways.push(giveaway.clone());
+    store.save(&giveaways)?;
+
+    Ok(giveaway)
+}
+
+/// Converts the emoji typed by the host into a reaction the bot can add.
+///
+/// Unicode emojis may be given as shortcodes; custom emojis must be given as `<:name:id>`.
+fn parse_reaction_type(input: &str) -> Result<ReactionType, crate::Error> {
+    let input = input.trim();
+    match ReactionMatcher::parse(input) {
+        ReactionMatcher::Unicode(emoji) => Ok(ReactionType::Unicode(emoji)),
+        ReactionMatcher::Custom(_) if input.starts_with('<') => Ok(ReactionType::try_from(input)?),
+        _ => Err(format!("Unknown emoji '{}'. Use a unicode emoji or a custom emoji like <:name:id>.", input).into()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn giveaway(message_id: u64, ends_at: i64) -> Giveaway {
+        Giveaway {
+            message_id: MessageId::new(message_id),
+            channel_id: ChannelId::new(200),
+            guild_id: Some(GuildId::new(100)),
+            host_id: UserId::new(1),
+            prize: "Prize".to_string(),
+            emoji: ReactionType::Unicode(DEFAULT_GIVEAWAY_EMOJI.to_string()),
+            winners: 1,
+            ends_at,
+            status: GiveawayStatus::Running,
+            draws: Vec::new(),
+            pending_candidate_list: None,
+            is_drawing: false,
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut giveaway = giveaway(1, 100);
+
+        assert!(!giveaway.is_due(99));
+        assert!(giveaway.is_due(100));
+        giveaway.status = GiveawayStatus::Ended;
+        assert!(!giveaway.is_due(200));
+    }
+
+    #[test]
+    fn test_pending_draw() {
+        let mut giveaway = giveaway(1, 100);
+        assert!(giveaway.pending_draw().is_none());
+
+        giveaway.draws.push(GiveawayDraw {
+            seed: 1,
+            candidate_hash: String::new(),
+            candidate_count: 1,
+            winners: vec![UserId::new(2)],
+        });
+        assert!(giveaway.pending_draw().is_none());
+
+        giveaway.pending_candidate_list = Some("2\n".to_string());
+        assert_eq!(giveaway.pending_draw().map(|(draw, list)| (draw.seed, list)), Some((1, "2\n")));
+    }
+
+    #[test]
+    fn test_parse_reaction_type() {
+        assert_eq!(
+            parse_reaction_type(":tada:").unwrap(),
+            ReactionType::Unicode(DEFAULT_GIVEAWAY_EMOJI.to_string())
+        );
+        assert!(matches!(
+            parse_reaction_type("<:party:123>").unwrap(),
+            ReactionType::Custom { id, .. } if id.get() == 123
+        ));
+        assert!(parse_reaction_type("party").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use poise::serenity_prelude::{
+    CreateAllowedMentions, CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Http, Mentionable,
+    ReactionType, UserId,
+};
+
+use crate::services::giveaway::{Giveaway, GiveawayDraw, GiveawayStatus};
+
+/// Number of ended giveaways shown by `/giveaway list`.
+const ENDED_LIST_LIMIT: usize = 10;
+
+/// Replies to the giveaway message with the winners, the seed and the candidate list.
+///
+/// Only the winners are pinged, so that a prize like `@everyone` stays plain text.
+pub async fn announce_draw(
+    http: &Http,
+    giveaway: &Giveaway,
+    draw: &GiveawayDraw,
+    candidate_list: String,
+    is_reroll: bool,
+) -> Result<(), crate::Error> {
+    let title = if is_reroll { "🔁 Rerolled" } else { "🎉 Giveaway ended" };
+    let winners = if draw.winners.is_empty() {
+        "No one entered, so there are no winners.".to_string()
+    } else {
+        format!("Congratulations {}!", to_mention_list(&draw.winners))
+    };
+    let filename = format!("draw_candidates_{}.txt", giveaway.message_id);
+
+    let content = format!(
+        "{}: **{}**\n{}\n\nSeed: `{}`\nCandidates SHA-256: `{}` ({} candidates)\n  📎: {}",
+        title,
+        giveaway.prize,
+        winners,
+        draw.seed,
+        draw.candidate_hash,
+        draw.candidate_count,
+        filename,
+    );
+
+    giveaway
+        .channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(content)
+                .allowed_mentions(CreateAllowedMentions::new().users(draw.winners.clone()))
+                .reference_message((giveaway.channel_id, giveaway.message_id))
+                .add_file(CreateAttachment::bytes(candidate_list, filename)),
+        )
+        .await?;
+    Ok(())
+}
+
+pub fn giveaway_embed(giveaway: &Giveaway) -> CreateEmbed {
+    match (giveaway.status, giveaway.draws.first()) {
+        (GiveawayStatus::Ended, Some(draw)) => CreateEmbed::new()
+            .title(format!("🎉 {}", giveaway.prize))
+            .description(format!(
+                "Ended <t:{}:f>\nWinners: {}\nHosted by {}",
+                giveaway.ends_at,
+                if draw.winners.is_empty() { "none".to_string() } else { to_mention_list(&draw.winners) },
+                giveaway.host_id.mention(),
+            ))
+            .footer(CreateEmbedFooter::new("Giveaway")),
+        _ => running_giveaway_embed(
+            &giveaway.prize,
+            &giveaway.emoji,
+            giveaway.ends_at,
+            giveaway.winners,
+            giveaway.host_id,
+        ),
+    }
+}
+
+/// Embed of a giveaway that is still running, also used before it is posted.
+pub fn running_giveaway_embed(
+    prize: &str,
+    emoji: &ReactionType,
+    ends_at: i64,
+    winners: usize,
+    host_id: UserId,
+) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(format!("🎉 {}", prize))
+        .description(format!(
+            "React with {} to enter!\nEnds <t:{}:R> (<t:{}:f>)\nWinners: {}\nHosted by {}",
+            emoji,
+            ends_at,
+            ends_at,
+            winners,
+            host_id.mention(),
+        ))
+        .footer(CreateEmbedFooter::new("Giveaway"))
+}
+
+/// Lists the running giveaways of the server, then the latest ended ones.
+pub fn to_giveaway_list_text(giveaways: &[Giveaway], guild_id: Option<GuildId>) -> String {
+    let in_guild: Vec<&Giveaway> = giveaways.iter().filter(|giveaway| giveaway.guild_id == guild_id).collect();
+
+    let running: String = in_guild
+        .iter()
+        .filter(|giveaway| giveaway.status == GiveawayStatus::Running)
+        .map(|giveaway| {
+            format!(
+                "- **{}** ({} winners) ends <t:{}:R>: {}\n",
+                giveaway.prize, giveaway.winners, giveaway.ends_at, giveaway.message_url()
+            )
+        })
+        .collect();
+
+    let ended: String = in_guild
+        .iter()
+        .rev()
+        .filter(|giveaway| giveaway.status == GiveawayStatus::Ended)
+        .take(ENDED_LIST_LIMIT)
+        .map(|giveaway| {
+            format!(
+                "- **{}** ended <t:{}:R>, {} winners drawn: {}\n",
+                giveaway.prize,
+                giveaway.ends_at,
+                giveaway.previous_winners().len(),
+                giveaway.message_url()
+            )
+        })
+        .collect();
+
+    format!(
+        "🎉 Running giveaways:\n{}\nEnded giveaways (latest {}):\n{}",
+        if running.is_empty() { "None\n".to_string() } else { running },
+        ENDED_LIST_LIMIT,
+        if ended.is_empty() { "None\n".to_string() } else { ended },
+    )
+}
+
+fn to_mention_list(user_ids: &[UserId]) -> String {
+    user_ids.iter().map(|id| id.mention().to_string()).collect::<Vec<String>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::giveaway::tests::giveaway;
+
+    #[test]
+    fn test_giveaway_list_text() {
+        let mut ended = giveaway(2, 50);
+        ended.status = GiveawayStatus::Ended;
+        let mut other_guild = giveaway(3, 100);
+        other_guild.guild_id = Some(GuildId::new(999));
+
+        let text = to_giveaway_list_text(&[giveaway(1, 100), ended, other_guild], Some(GuildId::new(100)));
+
+        assert!(text.contains("ends <t:100:R>: https://discord.com/channels/100/200/1"));
+        assert!(text.contains("ended <t:50:R>, 0 winners drawn: https://discord.com/channels/100/200/2"));
+        assert!(!text.contains("/3"));
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use std::time::Duration;
+use poise::serenity_prelude::{GuildId, Http, MessageId, Timestamp};
+
+use crate::services::giveaway::{end_giveaway, GiveawayStore};
+
+/// How often the stored giveaways are checked for an end time that has passed.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Ends giveaways whose end time has passed, for as long as the bot runs.
+///
+/// End times are read from the store on every check, so giveaways that ended
+/// while the bot was offline are drawn right after it starts.
+pub async fn run_giveaway_scheduler(http: Arc<Http>, store: Arc<GiveawayStore>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let now = Timestamp::now().unix_timestamp();
+        // コマンドで抽選中のものは除く
+        let due: Vec<(Option<GuildId>, MessageId)> = store
+            .lock()
+            .await
+            .iter()
+            .filter(|giveaway| giveaway.is_due(now) && !giveaway.is_drawing)
+            .map(|giveaway| (giveaway.guild_id, giveaway.message_id))
+            .collect();
+
+        for (guild_id, message_id) in due {
+            if let Err(e) = end_giveaway(&http, &store, guild_id, message_id).await {
+                log::error!("Failed to end giveaway {}: {}", message_id, e);
+            }
+        }
+    }
+}
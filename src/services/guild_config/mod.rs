@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use poise::serenity_prelude::GuildId;
+use serde::{Deserialize, Serialize};
+
+use crate::services::reaction_attendance::{default_attendance_emojis, AttendanceEmoji, AttendanceStatus};
+use crate::services::reaction_users::filter::ReactionMatcher;
+use crate::utils::json_store::JsonStore;
+
+/// Settings of every server, saved across restarts
+pub type GuildConfigStore = JsonStore<HashMap<GuildId, GuildConfig>>;
+
+/// Settings of one server. Missing settings fall back to the defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuildConfig {
+    /// Emoji to status mapping of `/reaction_attendance`; `None` uses [`default_attendance_emojis`]
+    #[serde(default)]
+    pub attendance: Option<Vec<AttendanceEmoji>>,
+}
+
+impl GuildConfig {
+    pub fn attendance_emojis(&self) -> Vec<AttendanceEmoji> {
+        self.attendance.clone().unwrap_or_else(default_attendance_emojis)
+    }
+
+    /// Maps `emoji` to `status`, replacing the status of the same emoji.
+    ///
+    /// The first change copies the defaults, so that setting one emoji keeps the others.
+    ///
+    /// # Errors
+    /// Returns an error, leaving the mapping unchanged, for a custom emoji given by name, as
+    /// `/reaction_signup` does: any word passes as a name, so a typo would be saved and never match.
+    pub fn set_attendance_emoji(&mut self, emoji: &str, status: AttendanceStatus) -> Result<(), crate::Error> {
+        if let ReactionMatcher::Name(_) = ReactionMatcher::parse(emoji.trim()) {
+            return Err("Give custom emojis as `<:name:id>`, e.g. by picking them in the emoji menu.".into());
+        }
+
+        let emoji = display_emoji(emoji);
+        let emojis = self.attendance.get_or_insert_with(default_attendance_emojis);
+        emojis.retain(|mapping| !is_same_emoji(&mapping.emoji, &emoji));
+        emojis.push(AttendanceEmoji { emoji, status });
+        Ok(())
+    }
+
+    /// Removes the mapping of `emoji`. Returns `false` when it was not mapped.
+    pub fn remove_attendance_emoji(&mut self, emoji: &str) -> bool {
+        let emojis = self.attendance.get_or_insert_with(default_attendance_emojis);
+        let count = emojis.len();
+        emojis.retain(|mapping| !is_same_emoji(&mapping.emoji, emoji));
+        emojis.len() != count
+    }
+}
+
+/// Unicode emojis given as shortcodes are stored as the emoji itself, for display.
+fn display_emoji(emoji: &str) -> String {
+    match ReactionMatcher::parse(emoji.trim()) {
+        ReactionMatcher::Unicode(unicode) => unicode,
+        _ => emoji.trim().to_string(),
+    }
+}
+
+fn is_same_emoji(a: &str, b: &str) -> bool {
+    ReactionMatcher::parse(a.trim()) == ReactionMatcher::parse(b.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_remove_attendance_emoji() {
+        let mut config = GuildConfig::default();
+        assert_eq!(config.attendance_emojis(), default_attendance_emojis());
+
+        config.set_attendance_emoji(":white_check_mark:", AttendanceStatus::Maybe).unwrap();
+        config.set_attendance_emoji("<:late:123>", AttendanceStatus::Maybe).unwrap();
+        assert!(config.set_attendance_emoji(":late:", AttendanceStatus::NotAttending).is_err());
+        assert!(config.set_attendance_emoji("late", AttendanceStatus::NotAttending).is_err());
+        let emojis = config.attendance_emojis();
+        assert_eq!(emojis.len(), 4);
+        assert!(emojis.contains(&AttendanceEmoji { emoji: "✅".to_string(), status: AttendanceStatus::Maybe }));
+
+        assert!(config.remove_attendance_emoji(":x:"));
+        assert!(!config.remove_attendance_emoji("❌"));
+        assert_eq!(config.attendance_emojis().len(), 3);
+    }
+}
@@ -1 +1,14 @@
-pub mod reaction_users;
\ No newline at end of file
+pub mod giveaway;
+pub mod guild_config;
+pub mod reaction_attendance;
+pub mod reaction_draw;
+pub mod reaction_live;
+pub mod reaction_mentions;
+pub mod reaction_missing;
+pub mod reaction_query;
+pub mod reaction_rating;
+#[cfg(feature = "snapshots")]
+pub mod reaction_snapshot;
+pub mod reaction_teams;
+pub mod reaction_users;
+pub mod reaction_watch;
\ No newline at end of file
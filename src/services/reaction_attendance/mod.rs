@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use poise::serenity_prelude::{Message, User, UserId};
+use serde::{Deserialize, Serialize};
+
+use crate::services::reaction_users::build_reaction_report;
+use crate::services::reaction_users::filter::{ReactionMatcher, UserFilter};
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text, to_mentions,
+};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+/// What a reaction means on an attendance sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, poise::ChoiceParameter)]
+#[serde(rename_all = "snake_case")]
+pub enum AttendanceStatus {
+    #[name = "attending"]
+    #[name_localized("ja", "参加")]
+    Attending,
+    #[name = "maybe"]
+    #[name_localized("ja", "未定")]
+    Maybe,
+    #[name = "not attending"]
+    #[name_localized("ja", "不参加")]
+    NotAttending,
+}
+
+impl AttendanceStatus {
+    pub const ALL: [AttendanceStatus; 3] = [
+        AttendanceStatus::Attending,
+        AttendanceStatus::Maybe,
+        AttendanceStatus::NotAttending,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AttendanceStatus::Attending => "Attending",
+            AttendanceStatus::Maybe => "Maybe",
+            AttendanceStatus::NotAttending => "Not attending",
+        }
+    }
+}
+
+/// An emoji and the status it stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttendanceEmoji {
+    /// The emoji as shown in the report, in any form accepted by [`ReactionMatcher::parse`]
+    pub emoji: String,
+    pub status: AttendanceStatus,
+}
+
+/// Mapping used by servers that have not configured their own: ✅ / ❓ / ❌.
+pub fn default_attendance_emojis() -> Vec<AttendanceEmoji> {
+    [
+        ("✅", AttendanceStatus::Attending),
+        ("❓", AttendanceStatus::Maybe),
+        ("❌", AttendanceStatus::NotAttending),
+    ]
+    .into_iter()
+    .map(|(emoji, status)| AttendanceEmoji { emoji: emoji.to_string(), status })
+    .collect()
+}
+
+#[derive(Debug)]
+pub struct ReactionAttendanceParameter {
+    pub message: Message,
+    /// Emoji to status mapping of the server
+    pub emojis: Vec<AttendanceEmoji>,
+}
+
+/// Reactions sorted into attendance statuses.
+#[derive(Debug, Default)]
+pub struct AttendanceSheet {
+    /// Users of each status, in [`AttendanceStatus::ALL`] order
+    pub buckets: Vec<(AttendanceStatus, Vec<User>)>,
+    /// Users who chose more than one status, with the emojis they reacted with
+    pub conflicts: Vec<(User, Vec<String>)>,
+    /// Reactions that are not mapped to any status
+    pub ignored_emojis: Vec<String>,
+}
+
+impl AttendanceSheet {
+    /// Number of users who chose at least one status
+    pub fn responded_count(&self) -> usize {
+        self.buckets.iter().map(|(_, users)| users.len()).sum::<usize>() + self.conflicts.len()
+    }
+}
+
+/// Reports the reactions of the message as an attendance sheet.
+///
+/// Bots are left out. Users who reacted with emojis of different statuses are
+/// listed as conflicting instead of being counted in any status.
+pub async fn process_reaction_attendance(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionAttendanceParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+
+    let report_parameter = ReactionUsersParameter {
+        is_reaction_grouping: true,
+        is_show_count: true,
+        user_filter: UserFilter { exclude_bots: true, exclude_user: None },
+        ..ReactionUsersParameter::for_emojis(&parameter.message, [])
+    };
+    let report = build_reaction_report(ctx, &report_parameter).await?;
+    let sheet = to_attendance_sheet(&report, &parameter.emojis);
+
+    Ok(ReactionUsersResponse::text(
+        get_reaction_users_header_text(&report),
+        format!("{}{}", get_attendance_text(&sheet, &parameter.emojis), get_reaction_failures_text(&report)),
+    ))
+}
+
+/// Sorts the reactors of `report` into the statuses of `emojis`.
+pub fn to_attendance_sheet(report: &ReactionReport, emojis: &[AttendanceEmoji]) -> AttendanceSheet {
+    let matchers: Vec<(ReactionMatcher, AttendanceStatus)> = emojis
+        .iter()
+        .map(|mapping| (ReactionMatcher::parse(&mapping.emoji), mapping.status))
+        .collect();
+
+    // ユーザーごとに選んだ状態と絵文字を集める（リアクション順を保つ）
+    let mut order: Vec<&User> = Vec::new();
+    let mut chosen: HashMap<UserId, (Vec<AttendanceStatus>, Vec<String>)> = HashMap::new();
+    let mut ignored_emojis = Vec::new();
+
+    for reaction in &report.reactions {
+        let Some(status) = matchers
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(&reaction.reaction_type))
+            .map(|(_, status)| *status)
+        else {
+            ignored_emojis.push(reaction.emoji.clone());
+            continue;
+        };
+
+        for user in &reaction.users {
+            let (statuses, user_emojis) = chosen.entry(user.id).or_insert_with(|| {
+                order.push(user);
+                (Vec::new(), Vec::new())
+            });
+            if !statuses.contains(&status) {
+                statuses.push(status);
+            }
+            user_emojis.push(reaction.emoji.clone());
+        }
+    }
+
+    let mut sheet = AttendanceSheet {
+        buckets: AttendanceStatus::ALL.iter().map(|status| (*status, Vec::new())).collect(),
+        conflicts: Vec::new(),
+        ignored_emojis,
+    };
+    for user in order {
+        let (statuses, user_emojis) = &chosen[&user.id];
+        match statuses.as_slice() {
+            [status] => {
+                if let Some((_, users)) = sheet.buckets.iter_mut().find(|(bucket, _)| bucket == status) {
+                    users.push(user.clone());
+                }
+            }
+            _ => sheet.conflicts.push((user.clone(), user_emojis.clone())),
+        }
+    }
+    sheet
+}
+
+/// Lists each status with its users, the conflicting users and the totals.
+pub fn get_attendance_text(sheet: &AttendanceSheet, emojis: &[AttendanceEmoji]) -> String {
+    let buckets: String = sheet.buckets
+        .iter()
+        .map(|(status, users)| {
+            let status_emojis: Vec<&str> = emojis
+                .iter()
+                .filter(|mapping| mapping.status == *status)
+                .map(|mapping| mapping.emoji.as_str())
+                .collect();
+            format!(
+                "  {} {}: {:>4}: {}\n",
+                status_emojis.join(" "),
+                status.label(),
+                users.len(),
+                to_user_list_text(users),
+            )
+        })
+        .collect();
+
+    let conflicts = if sheet.conflicts.is_empty() {
+        String::new()
+    } else {
+        let users: Vec<User> = sheet.conflicts.iter().map(|(user, _)| user.clone()).collect();
+        let details = sheet.conflicts
+            .iter()
+            .map(|(user, user_emojis)| format!("{} ({})", user.name, user_emojis.join(" ")))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            "  ⚠️ Conflicting: {:>4}: {}  {}\n",
+            users.len(),
+            to_user_list_text(&users),
+            details,
+        )
+    };
+
+    let totals = sheet.buckets
+        .iter()
+        .map(|(status, users)| format!("{} {}", users.len(), status.label().to_lowercase()))
+        .chain(std::iter::once(format!("{} conflicting", sheet.conflicts.len())))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let ignored = if sheet.ignored_emojis.is_empty() {
+        String::new()
+    } else {
+        format!("Ignored reactions (no status): {}\n", sheet.ignored_emojis.join(" "))
+    };
+
+    format!(
+        "Attendance:\n{}{}\nTotals: {} ({} responded)\n{}",
+        buckets,
+        conflicts,
+        totals,
+        sheet.responded_count(),
+        ignored,
+    )
+}
+
+fn to_user_list_text(users: &[User]) -> String {
+    if users.is_empty() {
+        "-".to_string()
+    } else {
+        let mentions = to_mentions(users);
+        format!("{}```{}```", mentions, mentions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+
+    fn report() -> ReactionReport {
+        let fetched = vec![
+            fetched("✅", vec![user(10, "alice"), user(11, "bob"), user(12, "carol")]),
+            fetched("👍", vec![user(10, "alice"), user(13, "dave")]),
+            fetched("❌", vec![user(12, "carol"), user(14, "erin")]),
+            fetched("🎉", vec![user(15, "frank")]),
+        ];
+        ReactionReport::new(&message(user(1, "author")), String::new(), fetched, false)
+    }
+
+    fn emojis() -> Vec<AttendanceEmoji> {
+        let mut emojis = default_attendance_emojis();
+        emojis.push(AttendanceEmoji { emoji: ":thumbsup:".to_string(), status: AttendanceStatus::Attending });
+        emojis
+    }
+
+    fn names(users: &[User]) -> Vec<&str> {
+        users.iter().map(|user| user.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_attendance_buckets_and_conflicts() {
+        let sheet = to_attendance_sheet(&report(), &emojis());
+
+        assert_eq!(names(&sheet.buckets[0].1), vec!["alice", "bob", "dave"]);
+        assert!(sheet.buckets[1].1.is_empty());
+        assert_eq!(names(&sheet.buckets[2].1), vec!["erin"]);
+        assert_eq!(sheet.conflicts.len(), 1);
+        assert_eq!(sheet.conflicts[0].0.name, "carol");
+        assert_eq!(sheet.conflicts[0].1, vec!["✅", "❌"]);
+        assert_eq!(sheet.ignored_emojis, vec!["🎉"]);
+        assert_eq!(sheet.responded_count(), 5);
+    }
+
+    #[test]
+    fn test_attendance_text_totals() {
+        let text = get_attendance_text(&to_attendance_sheet(&report(), &emojis()), &emojis());
+
+        assert!(text.contains("✅ :thumbsup: Attending:    3:"));
+        assert!(text.contains("❓ Maybe:    0: -"));
+        assert!(text.contains("carol (✅ ❌)"));
+        assert!(text.contains("Totals: 3 attending, 0 maybe, 1 not attending, 1 conflicting (5 responded)"));
+        assert!(text.contains("Ignored reactions (no status): 🎉"));
+    }
+}
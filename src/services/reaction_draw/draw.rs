@@ -0,0 +1,130 @@
+use poise::serenity_prelude::UserId;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+
+/// Picks `winners` distinct users from `candidates`, reproducibly from `seed`.
+///
+/// The algorithm is fixed so that anyone can re-run the draw:
+/// 1. Sort the candidates by user ID, ascending.
+/// 2. Seed ChaCha20 with `ChaCha20Rng::seed_from_u64(seed)`.
+/// 3. Run a partial Fisher–Yates shuffle: for `i` in `0..winners`, swap position `i`
+///    with `i + r`, where `r` is drawn uniformly below `len - i` by rejection sampling
+///    on `next_u64` (see [`uniform_below`]).
+/// 4. The first `winners` positions are the winners, in draw order.
+///
+/// When there are fewer candidates than winners, every candidate wins.
+pub fn draw_winners(candidates: &[UserId], winners: usize, seed: u64) -> Vec<UserId> {
+    let mut pool = sorted_candidates(candidates);
+    let winners = winners.min(pool.len());
+
+    shuffle_prefix(&mut pool, winners, &mut seeded_rng(seed));
+
+    pool.truncate(winners);
+    pool
+}
+
+/// The generator behind every seeded draw: `ChaCha20Rng::seed_from_u64(seed)`.
+pub fn seeded_rng(seed: u64) -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(seed)
+}
+
+/// Partial Fisher–Yates shuffle: afterwards, the first `count` items are a uniform
+/// random selection in random order (step 3 of [`draw_winners`]).
+///
+/// Pass `items.len()` as `count` to shuffle the whole slice.
+pub fn shuffle_prefix<T>(items: &mut [T], count: usize, rng: &mut impl RngCore) {
+    for i in 0..count.min(items.len()) {
+        let j = i + uniform_below(rng, (items.len() - i) as u64) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// SHA-256 of the candidate list, as lowercase hex.
+///
+/// The hashed text is [`candidate_list_text`], i.e. the sorted user IDs, one per line.
+pub fn candidate_hash(candidates: &[UserId]) -> String {
+    Sha256::digest(candidate_list_text(candidates).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Sorted, deduplicated user IDs, one per line with a trailing newline.
+pub fn candidate_list_text(candidates: &[UserId]) -> String {
+    sorted_candidates(candidates)
+        .iter()
+        .map(|id| format!("{}\n", id))
+        .collect()
+}
+
+fn sorted_candidates(candidates: &[UserId]) -> Vec<UserId> {
+    let mut sorted = candidates.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    sorted
+}
+
+/// Uniform integer in `0..bound` without modulo bias.
+///
+/// Values of `next_u64` at or above the largest multiple of `bound` are redrawn.
+fn uniform_below(rng: &mut impl RngCore, bound: u64) -> u64 {
+    let zone = u64::MAX - (u64::MAX % bound);
+    loop {
+        let value = rng.next_u64();
+        if value < zone {
+            return value % bound;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[u64]) -> Vec<UserId> {
+        ids.iter().map(|&id| UserId::new(id)).collect()
+    }
+
+    #[test]
+    fn test_draw_is_reproducible() {
+        let candidates = ids(&[5, 3, 9, 1, 7, 2, 8]);
+
+        let first = draw_winners(&candidates, 3, 42);
+        let shuffled = ids(&[8, 2, 7, 1, 9, 3, 5]);
+
+        assert_eq!(first, draw_winners(&shuffled, 3, 42));
+        assert_eq!(first.len(), 3);
+        let mut unique = first.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), 3);
+        assert!(first.iter().all(|id| candidates.contains(id)));
+    }
+
+    #[test]
+    fn test_draw_depends_on_seed() {
+        let candidates: Vec<UserId> = (1..=50).map(UserId::new).collect();
+
+        assert_ne!(draw_winners(&candidates, 5, 1), draw_winners(&candidates, 5, 2));
+    }
+
+    #[test]
+    fn test_draw_more_winners_than_candidates() {
+        let mut winners = draw_winners(&ids(&[2, 1]), 5, 0);
+        winners.sort();
+
+        assert_eq!(winners, ids(&[1, 2]));
+        assert!(draw_winners(&[], 1, 0).is_empty());
+    }
+
+    #[test]
+    fn test_candidate_hash() {
+        assert_eq!(candidate_list_text(&ids(&[3, 1, 3])), "1\n3\n");
+        assert_eq!(candidate_hash(&ids(&[3, 1])), candidate_hash(&ids(&[1, 3])));
+        // echo -n "" | sha256sum
+        assert_eq!(
+            candidate_hash(&[]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use poise::serenity_prelude::{CreateAttachment, Mentionable, Message, UserId};
+
+use crate::services::reaction_draw::draw::{candidate_hash, candidate_list_text, draw_winners};
+use crate::services::reaction_users::build_reaction_report;
+use crate::services::reaction_users::filter::UserFilter;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text,
+};
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+pub mod draw;
+
+#[derive(Debug)]
+pub struct ReactionDrawParameter {
+    pub message: Message,
+    /// Number of winners to pick
+    pub winners: usize,
+    /// Only users who reacted with this emoji are candidates
+    pub emoji: Option<String>,
+    /// Seed of the draw; a random seed is used when `None`
+    pub seed: Option<u64>,
+}
+
+/// Draws winners among the users who reacted to the message.
+///
+/// Bots and the message author are never candidates. The reply shows the seed and the
+/// SHA-256 of the sorted candidate list, and attaches that list, so that anyone can
+/// re-run the draw (see [`draw_winners`]).
+///
+/// Returns the reply with the winners, who are the only users it should ping.
+///
+/// # Errors
+/// Returns an error when `winners` is zero or when no one can win.
+pub async fn process_reaction_draw(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionDrawParameter,
+) -> Result<(ReactionUsersResponse, Vec<UserId>), crate::Error> {
+
+    if parameter.winners == 0 {
+        return Err("The number of winners must be at least 1.".into());
+    }
+
+    // 候補者（リアクションしたユーザー）を取得
+    let report_parameter = ReactionUsersParameter {
+        user_filter: UserFilter {
+            exclude_bots: true,
+            exclude_user: Some(parameter.message.author.id),
+        },
+        ..ReactionUsersParameter::for_emojis(&parameter.message, parameter.emoji.as_deref())
+    };
+    let report = build_reaction_report(ctx, &report_parameter).await?;
+    let candidates: Vec<UserId> = report.users.iter().map(|user| user.id).collect();
+
+    if candidates.is_empty() {
+        return Err("No one can win: no one (other than bots and the author) reacted.".into());
+    }
+
+    let seed = parameter.seed.unwrap_or_else(rand::random);
+    let winners = draw_winners(&candidates, parameter.winners, seed);
+
+    let winner_lines: String = winners
+        .iter()
+        .enumerate()
+        .map(|(index, id)| format!("{}. {}\n", index + 1, id.mention()))
+        .collect();
+    let shortage = if winners.len() < parameter.winners {
+        format!(" (only {} candidates)", candidates.len())
+    } else {
+        String::new()
+    };
+
+    let filename = format!("draw_candidates_{}.txt", report.message.id);
+    let content = format!(
+        r###"🎲 Draw: {} of {} candidates ({}){}
+
+Winners:
+{}
+Seed: `{}`
+Candidates SHA-256: `{}`
+  📎: {}
+{}"###,
+        winners.len(),
+        candidates.len(),
+        parameter.emoji.as_deref().unwrap_or("any reaction"),
+        shortage,
+        winner_lines,
+        seed,
+        candidate_hash(&candidates),
+        filename,
+        get_reaction_failures_text(&report),
+    );
+
+    let mut response = ReactionUsersResponse::text(get_reaction_users_header_text(&report), content);
+    response.attachments.push(CreateAttachment::bytes(candidate_list_text(&candidates), filename));
+    Ok((response, winners))
+}
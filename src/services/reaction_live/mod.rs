@@ -0,0 +1,194 @@
+use poise::serenity_prelude::{
+    ChannelId, CreateAllowedMentions, CreateMessage, EditMessage, GuildId, Http, Message, MessageId,
+    Timestamp, UserId,
+};
+use poise::serenity_prelude::Error as SerenityError;
+use serde::{Deserialize, Serialize};
+
+use crate::services::reaction_live::render::{live_footer, render_live_summary};
+use crate::utils::json_store::JsonStore;
+
+pub mod queue;
+pub mod render;
+pub mod updater;
+
+pub use queue::{schedule_live_update, LiveUpdateQueue};
+
+/// Live summaries, one per summary message, saved across restarts
+pub type LiveSummaryStore = JsonStore<Vec<LiveSummary>>;
+
+/// How many live summaries one server can run at the same time
+pub const MAX_LIVE_SUMMARIES_PER_GUILD: usize = 10;
+
+/// Default lifetime of a live summary, in seconds
+pub const DEFAULT_LIVE_DURATION: i64 = 24 * 60 * 60;
+
+/// Longest lifetime of a live summary, in seconds
+pub const MAX_LIVE_DURATION: i64 = 30 * 24 * 60 * 60;
+
+/// A summary message that is edited as the reactions of a message change.
+///
+/// A message can have one summary per channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveSummary {
+    /// The message whose reactions are summarized
+    pub message_id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    /// The summary message posted by the bot
+    pub summary_channel_id: ChannelId,
+    pub summary_message_id: MessageId,
+    pub created_by: UserId,
+    /// Seconds since the Unix epoch; the summary is no longer updated after this
+    pub expires_at: i64,
+    pub is_show_count: bool,
+}
+
+#[derive(Debug)]
+pub struct LiveSummaryParameter {
+    pub message: Message,
+    /// Lifetime in seconds
+    pub duration: i64,
+    pub is_show_count: bool,
+}
+
+impl LiveSummary {
+    pub fn message_url(&self) -> String {
+        self.message_id.link(self.channel_id, self.guild_id)
+    }
+
+    pub fn summary_url(&self) -> String {
+        self.summary_message_id.link(self.summary_channel_id, self.guild_id)
+    }
+
+    /// Whether `self` is the summary of `message_id` posted in `summary_channel_id`
+    pub fn is_summary_of(&self, message_id: MessageId, summary_channel_id: ChannelId) -> bool {
+        self.message_id == message_id && self.summary_channel_id == summary_channel_id
+    }
+}
+
+/// Posts a live summary of the message in the channel of the command.
+///
+/// When the message already has a live summary in this channel, that summary is edited
+/// and its expiry is replaced instead. Summaries in other channels are kept.
+///
+/// # Errors
+/// Returns an error when the server runs too many live summaries or the summary cannot be posted.
+pub async fn start_live_summary(
+    ctx: crate::Context<'_>,
+    parameter: &LiveSummaryParameter,
+) -> Result<LiveSummary, crate::Error> {
+    let store = &ctx.data().live_summaries;
+    let message = &parameter.message;
+    let guild_id = message.guild_id.or(ctx.guild_id());
+
+    let existing = {
+        let summaries = store.lock().await;
+        let existing = summaries
+            .iter()
+            .find(|summary| summary.is_summary_of(message.id, ctx.channel_id()))
+            .cloned();
+        let count = summaries
+            .iter()
+            .filter(|summary| summary.guild_id == guild_id && !summary.is_summary_of(message.id, ctx.channel_id()))
+            .count();
+        if count >= MAX_LIVE_SUMMARIES_PER_GUILD {
+            return Err(format!(
+                "This server already runs {} live summaries. Wait for one to expire or delete its message.",
+                MAX_LIVE_SUMMARIES_PER_GUILD
+            ).into());
+        }
+        existing
+    };
+
+    let expires_at = Timestamp::now().unix_timestamp() + parameter.duration;
+    let footer = live_footer(expires_at, false);
+    let text = render_live_summary(ctx.http(), message, guild_id, parameter.is_show_count, &footer).await;
+
+    // 既存のまとめを編集できない場合（削除済みなど）は新しく投稿する
+    let edited = match &existing {
+        Some(existing) => existing
+            .summary_channel_id
+            .edit_message(ctx.http(), existing.summary_message_id, EditMessage::new().content(&text))
+            .await
+            .map(|_| existing.summary_message_id)
+            .ok(),
+        None => None,
+    };
+    let summary_message_id = match edited {
+        Some(summary_message_id) => summary_message_id,
+        None => {
+            let posted = ctx
+                .channel_id()
+                .send_message(
+                    ctx.http(),
+                    CreateMessage::new().content(&text).allowed_mentions(CreateAllowedMentions::new()),
+                )
+                .await?;
+            posted.id
+        }
+    };
+
+    let summary = LiveSummary {
+        message_id: message.id,
+        channel_id: message.channel_id,
+        guild_id,
+        summary_channel_id: ctx.channel_id(),
+        summary_message_id,
+        created_by: ctx.author().id,
+        expires_at,
+        is_show_count: parameter.is_show_count,
+    };
+
+    let mut summaries = store.lock().await;
+    summaries.retain(|other| !other.is_summary_of(message.id, summary.summary_channel_id));
+    summaries.push(summary.clone());
+    store.save(&summaries)?;
+    Ok(summary)
+}
+
+/// Fetches the reactions of the message again and edits the summary.
+///
+/// With `is_final`, the summary says that it is no longer updated.
+pub async fn update_live_summary(http: &Http, summary: &LiveSummary, is_final: bool) -> Result<(), crate::Error> {
+    let message = summary.channel_id.message(http, summary.message_id).await?;
+    let footer = live_footer(summary.expires_at, is_final);
+    let text = render_live_summary(http, &message, summary.guild_id, summary.is_show_count, &footer).await;
+
+    summary
+        .summary_channel_id
+        .edit_message(http, summary.summary_message_id, EditMessage::new().content(text))
+        .await?;
+    Ok(())
+}
+
+/// Whether `error` means that a message no longer exists.
+pub fn is_unknown_message(error: &crate::Error) -> bool {
+    match error.downcast_ref::<SerenityError>() {
+        Some(SerenityError::Http(http_error)) => http_error.status_code().is_some_and(|status| status.as_u16() == 404),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_summary_of() {
+        let summary = LiveSummary {
+            message_id: MessageId::new(1),
+            channel_id: ChannelId::new(2),
+            guild_id: Some(GuildId::new(3)),
+            summary_channel_id: ChannelId::new(4),
+            summary_message_id: MessageId::new(5),
+            created_by: UserId::new(6),
+            expires_at: 100,
+            is_show_count: true,
+        };
+
+        assert!(summary.is_summary_of(MessageId::new(1), ChannelId::new(4)));
+        assert!(!summary.is_summary_of(MessageId::new(1), ChannelId::new(2)));
+        assert!(!summary.is_summary_of(MessageId::new(5), ChannelId::new(4)));
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use poise::serenity_prelude::MessageId;
+
+use crate::services::reaction_live::LiveSummaryStore;
+
+/// Time to wait after a reaction event before editing the summary.
+///
+/// Events arriving in the meantime are covered by the same edit, so a burst of
+/// reactions costs one edit instead of one per reaction.
+pub const LIVE_UPDATE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Summarized messages whose summaries wait to be edited, with the time the edit is due.
+#[derive(Debug, Default)]
+pub struct LiveUpdateQueue {
+    pending: Mutex<HashMap<MessageId, Instant>>,
+}
+
+impl LiveUpdateQueue {
+    /// Schedules an edit of the summaries of `message_id` after [`LIVE_UPDATE_DEBOUNCE`].
+    ///
+    /// An edit that is already scheduled is not postponed, so that a steady stream of
+    /// reactions still updates the summary regularly.
+    pub fn schedule(&self, message_id: MessageId, now: Instant) {
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(message_id)
+            .or_insert(now + LIVE_UPDATE_DEBOUNCE);
+    }
+
+    /// Removes and returns the messages whose edit is due at `now`.
+    pub fn take_due(&self, now: Instant) -> Vec<MessageId> {
+        let mut pending = self.pending.lock().unwrap();
+        let due: Vec<MessageId> = pending
+            .iter()
+            .filter(|(_, due_at)| **due_at <= now)
+            .map(|(message_id, _)| *message_id)
+            .collect();
+        for message_id in &due {
+            pending.remove(message_id);
+        }
+        due
+    }
+}
+
+/// Schedules an edit when `message_id` has a live summary.
+pub async fn schedule_live_update(store: &LiveSummaryStore, queue: &LiveUpdateQueue, message_id: MessageId) {
+    if store.lock().await.iter().any(|summary| summary.message_id == message_id) {
+        queue.schedule(message_id, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_update_queue_debounce() {
+        let queue = LiveUpdateQueue::default();
+        let start = Instant::now();
+
+        queue.schedule(MessageId::new(1), start);
+        queue.schedule(MessageId::new(1), start + Duration::from_secs(3));
+        queue.schedule(MessageId::new(2), start + Duration::from_secs(3));
+
+        assert!(queue.take_due(start + Duration::from_secs(4)).is_empty());
+        assert_eq!(queue.take_due(start + LIVE_UPDATE_DEBOUNCE), vec![MessageId::new(1)]);
+        assert!(queue.take_due(start + LIVE_UPDATE_DEBOUNCE).is_empty());
+        assert_eq!(queue.take_due(start + Duration::from_secs(10)), vec![MessageId::new(2)]);
+    }
+}
@@ -0,0 +1,109 @@
+use poise::serenity_prelude::{GuildId, Http, Message, Timestamp};
+
+use crate::services::reaction_users::apply_user_options;
+use crate::services::reaction_users::filter::UserFilter;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_empty_text, get_reaction_failures_text, get_reaction_grouping_text,
+    get_reaction_users_header_text,
+};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::ReactionUsersParameter;
+use crate::services::reaction_users::utils::{fetch_reactions, filter_reactions_by_user};
+use crate::utils::message_splitter::{split_message, MESSAGE_CHARACTER_LIMIT};
+
+/// Room left in the summary for the truncation note and the footer
+const FOOTER_RESERVE: usize = 200;
+
+/// The footer of a summary that is updated until `expires_at`, or of its last edit with `is_final`.
+pub fn live_footer(expires_at: i64, is_final: bool) -> String {
+    let now = Timestamp::now().unix_timestamp();
+    if is_final {
+        format!("🔒 Final: updated <t:{}:f>, no longer updated", now)
+    } else {
+        format!("🔄 Live: updated <t:{}:R>, until <t:{}:f>", now, expires_at)
+    }
+}
+
+/// The grouped report of `/reaction_members`, followed by `footer`.
+///
+/// `guild_id` is the server of the message, since messages fetched over HTTP do not carry one.
+pub async fn render_live_summary(
+    http: &Http,
+    message: &Message,
+    guild_id: Option<GuildId>,
+    is_show_count: bool,
+    footer: &str,
+) -> String {
+    let parameter = ReactionUsersParameter {
+        is_reaction_grouping: true,
+        is_show_count,
+        // 集計用に付けたBotのリアクションは人数に含めない
+        user_filter: UserFilter { exclude_bots: true, exclude_user: None },
+        ..ReactionUsersParameter::for_emojis(message, [])
+    };
+
+    let mut fetched = fetch_reactions(http, message, &parameter.reaction_filter).await;
+    filter_reactions_by_user(&parameter.user_filter, &mut fetched);
+    let url = message.id.link(message.channel_id, guild_id);
+    let mut report = ReactionReport::new(message, url, fetched, false);
+    apply_user_options(&mut report, &parameter);
+
+    get_live_summary_text(&report, &parameter, footer)
+}
+
+/// Renders the summary like the text output of `/reaction_members`, within one message.
+pub fn get_live_summary_text(report: &ReactionReport, parameter: &ReactionUsersParameter, footer: &str) -> String {
+    let text = if report.is_empty() {
+        get_reaction_empty_text()
+    } else {
+        get_reaction_grouping_text(report, parameter)
+    };
+    let content = get_reaction_users_header_text(report) + &text + &get_reaction_failures_text(report);
+
+    let mut chunks = split_message(content.trim_start(), MESSAGE_CHARACTER_LIMIT - FOOTER_RESERVE);
+    let body = if chunks.len() > 1 {
+        format!("{}\n… Too many users to show; use /reaction_members for the full list.", chunks.swap_remove(0))
+    } else {
+        chunks.pop().unwrap_or_default()
+    };
+    format!("{}\n\n{}", body, footer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+    use crate::services::reaction_users::types::tests::parameter;
+
+    #[test]
+    fn test_live_summary_text() {
+        let message = message(user(1, "author"));
+        let report = ReactionReport::new(
+            &message,
+            "https://discord.com/channels/100/200/300".to_string(),
+            vec![fetched("✅", vec![user(10, "a"), user(11, "b")])],
+            false,
+        );
+        let mut parameter = parameter();
+        parameter.is_show_count = true;
+
+        let text = get_live_summary_text(&report, &parameter, "🔄 Live");
+
+        assert!(text.starts_with("Information\n"));
+        assert!(text.contains(&get_reaction_grouping_text(&report, &parameter)));
+        assert!(text.ends_with("\n\n🔄 Live"));
+    }
+
+    #[test]
+    fn test_live_summary_text_truncates() {
+        let message = message(user(1, "author"));
+        let users = (0..200).map(|id| user(1000 + id, "user")).collect();
+        let report = ReactionReport::new(&message, String::new(), vec![fetched("✅", users)], false);
+
+        let text = get_live_summary_text(&report, &parameter(), "🔄 Live");
+
+        assert!(text.chars().count() <= MESSAGE_CHARACTER_LIMIT);
+        assert!(text.contains("Too many users to show"));
+        assert!(text.ends_with("🔄 Live"));
+    }
+}
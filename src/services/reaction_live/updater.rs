@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use poise::serenity_prelude::{Http, Timestamp};
+
+use crate::services::reaction_live::{
+    is_unknown_message, update_live_summary, LiveSummary, LiveSummaryStore, LiveUpdateQueue,
+};
+
+/// How often due edits and expired summaries are looked for.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Edits live summaries whose debounce has passed, and finishes expired ones, for as long as the bot runs.
+///
+/// Every stored summary is edited once at startup, to show the reactions changed while the bot was offline.
+/// A summary whose message or summary message was deleted is dropped.
+pub async fn run_live_summary_updater(http: Arc<Http>, store: Arc<LiveSummaryStore>, queue: Arc<LiveUpdateQueue>) {
+    for summary in store.lock().await.iter() {
+        queue.schedule(summary.message_id, Instant::now());
+    }
+
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let now = Timestamp::now().unix_timestamp();
+        let expired: Vec<LiveSummary> = store
+            .lock()
+            .await
+            .iter()
+            .filter(|summary| summary.expires_at <= now)
+            .cloned()
+            .collect();
+        for summary in expired {
+            update(&http, &store, summary, true).await;
+        }
+
+        for message_id in queue.take_due(Instant::now()) {
+            let summaries: Vec<LiveSummary> = store
+                .lock()
+                .await
+                .iter()
+                .filter(|summary| summary.message_id == message_id)
+                .cloned()
+                .collect();
+            for summary in summaries {
+                update(&http, &store, summary, false).await;
+            }
+        }
+    }
+}
+
+/// Edits one summary; the store is not locked while the edit is sent.
+async fn update(http: &Http, store: &LiveSummaryStore, summary: LiveSummary, is_final: bool) {
+    let result = update_live_summary(http, &summary, is_final).await;
+    if let Err(e) = &result {
+        log::error!("Failed to update live summary {} of {}: {}", summary.summary_message_id, summary.message_id, e);
+    }
+
+    if is_final || result.as_ref().is_err_and(is_unknown_message) {
+        let mut summaries = store.lock().await;
+        // 更新中に作り直された、または期限を延ばしたまとめは残す
+        let count = summaries.len();
+        summaries.retain(|current| {
+            current.summary_message_id != summary.summary_message_id || current.expires_at != summary.expires_at
+        });
+        if summaries.len() != count
+            && let Err(e) = store.save(&summaries)
+        {
+            log::error!("Failed to save live summaries: {}", e);
+        }
+    }
+}
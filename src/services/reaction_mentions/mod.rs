@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use poise::futures_util::TryStreamExt;
+use poise::serenity_prelude::{Member, Message, RoleId, User, UserId};
+
+use crate::services::reaction_users::build_reaction_report;
+
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text, to_mentions,
+};
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+use crate::utils::config::{is_guild_members_intent_enabled, GUILD_MEMBERS_INTENT_ENV};
+use crate::utils::parsers::{parse_role_mentions, parse_user_mentions};
+
+#[derive(Debug)]
+pub struct ReactionMentionsParameter {
+    pub message: Message,
+    /// Only a reaction with this emoji counts as a response
+    pub emoji: Option<String>,
+}
+
+/// Mentioned users split by whether they reacted.
+#[derive(Debug, Default, PartialEq)]
+pub struct MentionStatus {
+    pub reacted: Vec<User>,
+    pub not_reacted: Vec<User>,
+    /// Reactors who were not mentioned
+    pub unmentioned: Vec<User>,
+}
+
+/// Compares the users mentioned in the message with the users who reacted to it.
+///
+/// Users are mentioned directly (`<@id>`) or through a role mention (`<@&id>`).
+/// Role mentions are expanded to their members only when the `GUILD_MEMBERS` intent is
+/// enabled; otherwise they are skipped with a warning. `@everyone` and `@here` are ignored.
+pub async fn process_reaction_mentions(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionMentionsParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+
+    let message = &parameter.message;
+
+    // リアクションしたユーザーを取得
+    let report_parameter = ReactionUsersParameter::for_emojis(message, parameter.emoji.as_deref());
+    let mut report = build_reaction_report(ctx, &report_parameter).await?;
+
+    // メンションされたユーザーを取得
+    let (mentioned, warnings) = fetch_mentioned_users(ctx, message).await?;
+    report.warnings.extend(warnings);
+
+    if mentioned.is_empty() {
+        return Ok(ReactionUsersResponse::text(
+            get_reaction_users_header_text(&report),
+            format!("\nNo users or roles are mentioned in the message.\n{}", get_reaction_failures_text(&report)),
+        ));
+    }
+
+    let status = classify_mentions(&mentioned, &report.users);
+    let reaction_text = parameter.emoji.as_deref().unwrap_or("any reaction");
+    let not_reacted = to_mentions(&status.not_reacted);
+
+    let content = format!(
+        r###"Mentioned: {} users
+Reacted with {}: {} / {}
+
+Not reacted ({}):
+{}
+```{}```
+
+Reacted ({}):
+{}
+
+Reacted but not mentioned ({}):
+{}
+{}"###,
+        mentioned.len(),
+        reaction_text,
+        status.reacted.len(),
+        mentioned.len(),
+        status.not_reacted.len(),
+        not_reacted,
+        not_reacted,
+        status.reacted.len(),
+        to_mentions(&status.reacted),
+        status.unmentioned.len(),
+        to_mentions(&status.unmentioned),
+        get_reaction_failures_text(&report),
+    );
+
+    Ok(ReactionUsersResponse::text(get_reaction_users_header_text(&report), content))
+}
+
+/// Splits the mentioned users by whether they reacted, and lists reactors who were not mentioned.
+///
+/// Mentioned users keep the order of the mentions; reactors keep the order of the reactions.
+pub fn classify_mentions(mentioned: &[User], reactors: &[User]) -> MentionStatus {
+    let reactor_ids: HashSet<UserId> = reactors.iter().map(|user| user.id).collect();
+    let mentioned_ids: HashSet<UserId> = mentioned.iter().map(|user| user.id).collect();
+
+    let (reacted, not_reacted) = mentioned
+        .iter()
+        .cloned()
+        .partition(|user| reactor_ids.contains(&user.id));
+
+    MentionStatus {
+        reacted,
+        not_reacted,
+        unmentioned: reactors
+            .iter()
+            .filter(|user| !mentioned_ids.contains(&user.id))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Resolves the user and role mentions in the message content to users, without duplicates.
+async fn fetch_mentioned_users(
+    ctx: crate::Context<'_>,
+    message: &Message,
+) -> Result<(Vec<User>, Vec<String>), crate::Error> {
+    let mut users: Vec<User> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::new();
+
+    for id in parse_user_mentions(&message.content).await {
+        let user_id = UserId::new(id);
+        if !seen.insert(user_id) {
+            continue;
+        }
+        // メッセージに含まれるユーザー情報を優先し、無ければAPIから取得
+        match message.mentions.iter().find(|user| user.id == user_id) {
+            Some(user) => users.push(user.clone()),
+            None => match user_id.to_user(ctx).await {
+                Ok(user) => users.push(user),
+                Err(e) => warnings.push(format!("Could not resolve {}: {}", user_id, e)),
+            },
+        }
+    }
+
+    let role_ids: Vec<RoleId> = parse_role_mentions(&message.content)
+        .await
+        .into_iter()
+        .map(RoleId::new)
+        .collect();
+    if role_ids.is_empty() {
+        return Ok((users, warnings));
+    }
+
+    if !is_guild_members_intent_enabled() {
+        warnings.push(format!(
+            "Role mentions were skipped. Set {}=true to expand them to their members.",
+            GUILD_MEMBERS_INTENT_ENV,
+        ));
+        return Ok((users, warnings));
+    }
+
+    // HTTPで取得したメッセージにはguild_idが含まれないため、コマンド実行元のサーバーを使う
+    let Some(guild_id) = message.guild_id.or(ctx.guild_id()) else {
+        warnings.push("Role mentions were skipped outside of a server.".to_string());
+        return Ok((users, warnings));
+    };
+
+    // ロールメンションをメンバーに展開
+    let members: Vec<Member> = guild_id.members_iter(ctx).try_collect().await?;
+    for member in members {
+        let is_mentioned = member.roles.iter().any(|role_id| role_ids.contains(role_id));
+        if is_mentioned && !member.user.bot && seen.insert(member.user.id) {
+            users.push(member.user);
+        }
+    }
+
+    Ok((users, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::user;
+
+    fn ids(users: &[User]) -> Vec<u64> {
+        users.iter().map(|user| user.id.get()).collect()
+    }
+
+    #[test]
+    fn test_classify_mentions() {
+        let mentioned = vec![user(1, "a"), user(2, "b"), user(3, "c")];
+        let reactors = vec![user(4, "d"), user(3, "c"), user(1, "a")];
+
+        let status = classify_mentions(&mentioned, &reactors);
+
+        assert_eq!(ids(&status.reacted), vec![1, 3]);
+        assert_eq!(ids(&status.not_reacted), vec![2]);
+        assert_eq!(ids(&status.unmentioned), vec![4]);
+    }
+
+    #[test]
+    fn test_classify_mentions_without_reactions() {
+        let status = classify_mentions(&[user(1, "a")], &[]);
+
+        assert_eq!(ids(&status.not_reacted), vec![1]);
+        assert!(status.reacted.is_empty());
+        assert!(status.unmentioned.is_empty());
+    }
+}
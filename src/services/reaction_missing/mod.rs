@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use poise::futures_util::TryStreamExt;
+use poise::serenity_prelude::{ChannelId, GuildChannel, Member, Mentionable, Message, Permissions, RoleId, User, UserId};
+
+use crate::services::reaction_users::build_reaction_report;
+use crate::services::reaction_users::filter::RoleFilter;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text, to_mentions,
+};
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+use crate::utils::config::{is_guild_members_intent_enabled, GUILD_MEMBERS_INTENT_ENV};
+
+#[derive(Debug)]
+pub struct ReactionMissingParameter {
+    pub message: Message,
+    /// Members of this role are expected to react
+    pub role: Option<RoleId>,
+    /// Members who can view this channel are expected to react
+    pub channel: Option<ChannelId>,
+    /// Only a reaction with this emoji counts as a response
+    pub emoji: Option<String>,
+}
+
+/// Lists the members of an audience who did not react to the message.
+///
+/// The audience is:
+/// - the members of `role`, when only a role is given
+/// - the members who can view `channel` (and have `role`, if given), when a channel is given
+/// - the members who can view the message's channel, when neither is given
+///
+/// Bots are never part of the audience.
+///
+/// # Errors
+/// Returns an error when the `GUILD_MEMBERS` intent is disabled, since listing the members of
+/// a server requires it, or when the message is not in a server.
+pub async fn process_reaction_missing(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionMissingParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+
+    if !is_guild_members_intent_enabled() {
+        return Err(format!(
+            "Listing server members requires the GUILD_MEMBERS intent. Set {}=true and enable \"Server Members Intent\" in the Discord Developer Portal.",
+            GUILD_MEMBERS_INTENT_ENV,
+        ).into());
+    }
+
+    // HTTPで取得したメッセージにはguild_idが含まれないため、コマンド実行元のサーバーを使う
+    let guild_id = parameter
+        .message
+        .guild_id
+        .or(ctx.guild_id())
+        .ok_or("This command can only be used on messages in a server.")?;
+
+    // リアクションしたユーザーを取得
+    let report_parameter = ReactionUsersParameter::for_emojis(&parameter.message, parameter.emoji.as_deref());
+    let report = build_reaction_report(ctx, &report_parameter).await?;
+    let reacted: HashSet<UserId> = report.users.iter().map(|user| user.id).collect();
+
+    // 対象メンバーを列挙
+    let members: Vec<Member> = guild_id.members_iter(ctx).try_collect().await?;
+
+    let role_filter = RoleFilter { include: parameter.role, exclude: None };
+    let audience_channel = match (parameter.role, parameter.channel) {
+        (Some(_), None) => None,
+        (_, Some(channel_id)) => Some(channel_id),
+        (None, None) => Some(parameter.message.channel_id),
+    };
+    let visibility = match audience_channel {
+        Some(channel_id) => Some(ChannelVisibility::fetch(ctx, channel_id).await?),
+        None => None,
+    };
+
+    let audience: Vec<&Member> = members
+        .iter()
+        .filter(|member| !member.user.bot)
+        .filter(|member| role_filter.is_match(guild_id, &member.roles))
+        .filter(|member| visibility.as_ref().is_none_or(|v| v.can_view(member)))
+        .collect();
+
+    let missing: Vec<User> = audience
+        .iter()
+        .filter(|member| !reacted.contains(&member.user.id))
+        .map(|member| member.user.clone())
+        .collect();
+
+    let audience_text = match (parameter.role, audience_channel) {
+        (Some(role_id), Some(channel_id)) => format!("{} in {}", role_id.mention(), channel_id.mention()),
+        (Some(role_id), None) => role_id.mention().to_string(),
+        (None, Some(channel_id)) => channel_id.mention().to_string(),
+        (None, None) => String::new(),
+    };
+    let reaction_text = parameter.emoji.as_deref().unwrap_or("any reaction");
+
+    let mentions = to_mentions(&missing);
+    let content = format!(
+        r###"Audience: {} ({} members)
+Reacted with {}: {} / {}
+
+Not reacted ({}):
+{}
+```{}```
+{}"###,
+        audience_text,
+        audience.len(),
+        reaction_text,
+        audience.len() - missing.len(),
+        audience.len(),
+        missing.len(),
+        mentions,
+        mentions,
+        get_reaction_failures_text(&report),
+    );
+
+    Ok(ReactionUsersResponse::text(get_reaction_users_header_text(&report), content))
+}
+
+/// Permission context needed to tell whether a member can view a channel.
+struct ChannelVisibility {
+    guild: poise::serenity_prelude::PartialGuild,
+    channel: GuildChannel,
+}
+
+impl ChannelVisibility {
+    /// Fetches the server roles and the channel. Threads are checked against their parent channel.
+    async fn fetch(ctx: crate::Context<'_>, channel_id: ChannelId) -> Result<Self, crate::Error> {
+        let mut channel = channel_id
+            .to_channel(ctx)
+            .await?
+            .guild()
+            .ok_or("The channel is not a server channel.")?;
+
+        if let Some(parent_id) = channel.parent_id.filter(|_| channel.thread_metadata.is_some()) {
+            channel = parent_id
+                .to_channel(ctx)
+                .await?
+                .guild()
+                .ok_or("The parent channel is not a server channel.")?;
+        }
+
+        let guild = channel.guild_id.to_partial_guild(ctx).await?;
+        Ok(Self { guild, channel })
+    }
+
+    fn can_view(&self, member: &Member) -> bool {
+        self.guild
+            .user_permissions_in(&self.channel, member)
+            .contains(Permissions::VIEW_CHANNEL)
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use poise::serenity_prelude::Message;
+
+use crate::services::reaction_query::parser::{evaluate_query, parse_query, unmatched_emojis};
+use crate::services::reaction_users::build_reaction_report;
+use crate::services::reaction_users::filter::UserFilter;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text, get_reaction_users_text,
+};
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse, UserSort};
+
+pub mod parser;
+
+#[derive(Debug)]
+pub struct ReactionQueryParameter {
+    pub message: Message,
+    pub expression: String,
+    pub user_filter: UserFilter,
+    pub sort: UserSort,
+}
+
+/// Evaluates a set expression over the users of each reaction and lists the matching users.
+///
+/// The expression is parsed before any reaction is fetched, so a malformed
+/// expression fails fast without calling the Discord API.
+pub async fn process_reaction_query(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionQueryParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+
+    let expr = parse_query(&parameter.expression)?;
+
+    let report_parameter = ReactionUsersParameter {
+        user_filter: parameter.user_filter.clone(),
+        sort: parameter.sort,
+        ..ReactionUsersParameter::for_emojis(&parameter.message, [])
+    };
+    let mut report = build_reaction_report(ctx, &report_parameter).await?;
+
+    // 式に一致したユーザーのみに絞り込む（リアクション順は維持）
+    let matched: HashSet<_> = evaluate_query(&expr, &report.reactions);
+    report.users.retain(|user| matched.contains(&user.id));
+
+    let unmatched_text: String = unmatched_emojis(&parameter.expression, &report.reactions)
+        .iter()
+        .map(|emoji| format!("\n⚠️ No one reacted with {}", emoji))
+        .collect();
+
+    Ok(ReactionUsersResponse::text(
+        get_reaction_users_header_text(&report),
+        format!(
+            "Query: {} ({} users)\n{}{}{}",
+            to_inline_code(&parameter.expression),
+            report.user_count(),
+            get_reaction_users_text(&report),
+            unmatched_text,
+            get_reaction_failures_text(&report),
+        ),
+    ))
+}
+
+/// Shows user input as inline code.
+///
+/// A backtick would end the code span and let the rest be rendered as markdown,
+/// so it is replaced with the look-alike `ˋ` (U+02CB).
+fn to_inline_code(text: &str) -> String {
+    format!("`{}`", text.replace('`', "\u{2cb}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_inline_code() {
+        assert_eq!(to_inline_code("✅ & !❌"), "`✅ & !❌`");
+        assert_eq!(to_inline_code("✅` @everyone `"), "`✅\u{2cb} @everyone \u{2cb}`");
+    }
+}
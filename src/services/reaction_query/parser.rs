@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use anyhow::{anyhow, Result};
+use poise::serenity_prelude::UserId;
+
+use crate::services::reaction_users::filter::ReactionMatcher;
+use crate::services::reaction_users::report::ReactionUsers;
+
+/// Set expression over the users of each reaction.
+///
+/// Precedence, from strongest to weakest:
+/// `!` (negation) > `&` (intersection), `-` (difference) > `|` (union).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryExpr {
+    /// Users who reacted with the emoji
+    Reaction(ReactionMatcher),
+    /// Reactors who did not react with the inner expression
+    Not(Box<QueryExpr>),
+    And(Box<QueryExpr>, Box<QueryExpr>),
+    Or(Box<QueryExpr>, Box<QueryExpr>),
+    Difference(Box<QueryExpr>, Box<QueryExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Emoji(String),
+    And,
+    Or,
+    Not,
+    Minus,
+    LeftParen,
+    RightParen,
+}
+
+/// Parses an expression such as `✅ & !❌ | 🎉`.
+///
+/// Emojis accept the same forms as the reaction filters (see [`ReactionMatcher::parse`]).
+///
+/// # Errors
+/// Returns an error describing the position of the problem when the expression is malformed.
+pub fn parse_query(input: &str) -> Result<QueryExpr> {
+    let tokens = tokenize(input);
+    if tokens.is_empty() {
+        return Err(anyhow!("The expression is empty."));
+    }
+
+    let mut parser = Parser { tokens, position: 0 };
+    let expr = parser.parse_or()?;
+
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(anyhow!("Unexpected {} at token {}.", describe(token), parser.position + 1)),
+    }
+}
+
+/// Evaluates the expression against the users of each reaction.
+///
+/// Negation is relative to every user who reacted to the message.
+pub fn evaluate_query(expr: &QueryExpr, reactions: &[ReactionUsers]) -> HashSet<UserId> {
+    match expr {
+        QueryExpr::Reaction(matcher) => reactions
+            .iter()
+            .filter(|reaction| matcher.is_match(&reaction.reaction_type))
+            .flat_map(|reaction| reaction.users.iter().map(|user| user.id))
+            .collect(),
+        QueryExpr::Not(inner) => {
+            let excluded = evaluate_query(inner, reactions);
+            all_reactors(reactions)
+                .into_iter()
+                .filter(|id| !excluded.contains(id))
+                .collect()
+        }
+        QueryExpr::And(left, right) => {
+            let right = evaluate_query(right, reactions);
+            evaluate_query(left, reactions).into_iter().filter(|id| right.contains(id)).collect()
+        }
+        QueryExpr::Or(left, right) => {
+            let mut result = evaluate_query(left, reactions);
+            result.extend(evaluate_query(right, reactions));
+            result
+        }
+        QueryExpr::Difference(left, right) => {
+            let right = evaluate_query(right, reactions);
+            evaluate_query(left, reactions).into_iter().filter(|id| !right.contains(id)).collect()
+        }
+    }
+}
+
+/// Emojis used in the expression that match no reaction on the message.
+pub fn unmatched_emojis(input: &str, reactions: &[ReactionUsers]) -> Vec<String> {
+    tokenize(input)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Emoji(emoji) => Some(emoji),
+            _ => None,
+        })
+        .filter(|emoji| {
+            let matcher = ReactionMatcher::parse(emoji);
+            !reactions.iter().any(|reaction| matcher.is_match(&reaction.reaction_type))
+        })
+        .collect()
+}
+
+fn all_reactors(reactions: &[ReactionUsers]) -> HashSet<UserId> {
+    reactions
+        .iter()
+        .flat_map(|reaction| reaction.users.iter().map(|user| user.id))
+        .collect()
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut emoji = String::new();
+
+    for c in input.chars() {
+        let operator = match c {
+            '&' => Some(Token::And),
+            '|' => Some(Token::Or),
+            '!' => Some(Token::Not),
+            '-' => Some(Token::Minus),
+            '(' => Some(Token::LeftParen),
+            ')' => Some(Token::RightParen),
+            _ => None,
+        };
+
+        if operator.is_some() || c.is_whitespace() {
+            if !emoji.is_empty() {
+                tokens.push(Token::Emoji(std::mem::take(&mut emoji)));
+            }
+            tokens.extend(operator);
+        } else {
+            emoji.push(c);
+        }
+    }
+
+    if !emoji.is_empty() {
+        tokens.push(Token::Emoji(emoji));
+    }
+    tokens
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Emoji(emoji) => format!("'{}'", emoji),
+        Token::And => "'&'".to_string(),
+        Token::Or => "'|'".to_string(),
+        Token::Not => "'!'".to_string(),
+        Token::Minus => "'-'".to_string(),
+        Token::LeftParen => "'('".to_string(),
+        Token::RightParen => "')'".to_string(),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<QueryExpr> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            expr = QueryExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<QueryExpr> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                    expr = QueryExpr::And(Box::new(expr), Box::new(self.parse_unary()?));
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    expr = QueryExpr::Difference(Box::new(expr), Box::new(self.parse_unary()?));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryExpr> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(QueryExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<QueryExpr> {
+        match self.next() {
+            Some(Token::Emoji(emoji)) => Ok(QueryExpr::Reaction(ReactionMatcher::parse(&emoji))),
+            Some(Token::LeftParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(expr),
+                    _ => Err(anyhow!("Missing ')' at token {}.", self.position)),
+                }
+            }
+            Some(token) => Err(anyhow!("Unexpected {} at token {}.", describe(&token), self.position)),
+            None => Err(anyhow!("The expression ends unexpectedly.")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poise::serenity_prelude::{ReactionType, User};
+    use crate::services::reaction_users::report::tests::user;
+
+    fn reaction(emoji: &str, users: Vec<User>) -> ReactionUsers {
+        ReactionUsers {
+            reaction_type: ReactionType::Unicode(emoji.to_string()),
+            emoji: emoji.to_string(),
+            count: users.len() as u64,
+            users,
+        }
+    }
+
+    fn ids(set: HashSet<UserId>) -> Vec<u64> {
+        let mut ids: Vec<u64> = set.into_iter().map(|id| id.get()).collect();
+        ids.sort();
+        ids
+    }
+
+    fn reactions() -> Vec<ReactionUsers> {
+        vec![
+            reaction("✅", vec![user(1, "a"), user(2, "b"), user(3, "c")]),
+            reaction("❌", vec![user(3, "c"), user(4, "d")]),
+            reaction("🎉", vec![user(4, "d"), user(5, "e")]),
+        ]
+    }
+
+    fn query(input: &str) -> Vec<u64> {
+        ids(evaluate_query(&parse_query(input).unwrap(), &reactions()))
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(query("✅ & !❌ | 🎉"), vec![1, 2, 4, 5]);
+        assert_eq!(query("✅ & (!❌ | 🎉)"), vec![1, 2]);
+        assert_eq!(query("✅ | ❌ & 🎉"), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(query("✅&❌"), vec![3]);
+        assert_eq!(query("✅ - ❌"), vec![1, 2]);
+        assert_eq!(query("!✅"), vec![4, 5]);
+        assert_eq!(query("!!✅"), vec![1, 2, 3]);
+        assert_eq!(query(":white_check_mark: & :x:"), vec![3]);
+        assert_eq!(query("👀"), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_query("").is_err());
+        assert!(parse_query("✅ &").is_err());
+        assert!(parse_query("(✅ | ❌").is_err());
+        assert!(parse_query("✅ ❌").is_err());
+        assert!(parse_query("✅ )").is_err());
+    }
+
+    #[test]
+    fn test_unmatched_emojis() {
+        assert_eq!(unmatched_emojis("✅ | 👀 & !🎉", &reactions()), vec!["👀".to_string()]);
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use anyhow::anyhow;
+use poise::serenity_prelude::{Message, User, UserId};
+
+use crate::services::reaction_users::build_reaction_report;
+use crate::services::reaction_users::filter::{ReactionMatcher, UserFilter};
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text, to_mentions,
+};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+/// Width of the longest bar of the distribution.
+const HISTOGRAM_WIDTH: usize = 20;
+
+/// Largest absolute score, so that sums of any number of reactors stay far from overflowing.
+const MAX_SCORE: i64 = 1_000_000;
+
+/// An emoji and the score it stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreEmoji {
+    pub emoji: String,
+    pub score: i64,
+}
+
+/// Keycap emojis 0️⃣ to 9️⃣ and 🔟, scoring their number.
+pub fn default_score_emojis() -> Vec<ScoreEmoji> {
+    let keycaps = (0..=9).map(|n| (format!("{}\u{fe0f}\u{20e3}", n), n));
+    keycaps
+        .chain(std::iter::once(("🔟".to_string(), 10)))
+        .map(|(emoji, score)| ScoreEmoji { emoji, score })
+        .collect()
+}
+
+/// Parses a comma-separated `emoji=score` list, e.g. `😡=1, 😐=3, 😍=5`.
+///
+/// # Errors
+/// Returns an error for a pair without `=`, a score that is not an integer from
+/// -[`MAX_SCORE`] to [`MAX_SCORE`], or an empty list.
+pub fn parse_score_emojis(input: &str) -> anyhow::Result<Vec<ScoreEmoji>> {
+    let emojis = input
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (emoji, score) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| anyhow!("'{}' must be written as emoji=score, e.g. ⭐=5.", pair))?;
+            let score = score
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|score| (-MAX_SCORE..=MAX_SCORE).contains(score))
+                .ok_or_else(|| anyhow!("The score of '{}' must be an integer from -{} to {}.", pair, MAX_SCORE, MAX_SCORE))?;
+            Ok(ScoreEmoji { emoji: emoji.trim().to_string(), score })
+        })
+        .collect::<anyhow::Result<Vec<ScoreEmoji>>>()?;
+
+    if emojis.is_empty() {
+        return Err(anyhow!("Give at least one emoji=score pair."));
+    }
+    Ok(emojis)
+}
+
+#[derive(Debug)]
+pub struct ReactionRatingParameter {
+    pub message: Message,
+    /// Emoji to score mapping
+    pub emojis: Vec<ScoreEmoji>,
+}
+
+/// Reactions read as scores.
+#[derive(Debug, Default)]
+pub struct RatingSheet {
+    /// The score of each user who chose exactly one, in reaction order
+    pub scores: Vec<(User, i64)>,
+    /// Users who chose more than one score, with the emojis they reacted with
+    pub multiple: Vec<(User, Vec<String>)>,
+    /// One row per distinct score on the message, highest first.
+    /// Emojis sharing a score are joined into one label, so that their users are counted once.
+    pub rows: Vec<(String, i64)>,
+}
+
+impl RatingSheet {
+    pub fn average(&self) -> Option<f64> {
+        if self.scores.is_empty() {
+            return None;
+        }
+        let total: i64 = self.scores.iter().map(|(_, score)| score).sum();
+        Some(total as f64 / self.scores.len() as f64)
+    }
+
+    /// The middle score, or the mean of the two middle scores for an even count
+    pub fn median(&self) -> Option<f64> {
+        let mut scores: Vec<i64> = self.scores.iter().map(|(_, score)| *score).collect();
+        scores.sort();
+        let middle = scores.len() / 2;
+        match scores.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(scores[middle] as f64),
+            _ => Some((scores[middle - 1] + scores[middle]) as f64 / 2.0),
+        }
+    }
+
+    /// Users who chose `score`
+    pub fn users_with(&self, score: i64) -> Vec<User> {
+        self.scores
+            .iter()
+            .filter(|(_, user_score)| *user_score == score)
+            .map(|(user, _)| user.clone())
+            .collect()
+    }
+}
+
+/// Reports the reactions of the message as ratings.
+///
+/// Bots and the message author (who usually adds the score reactions) are left out.
+/// Users who reacted with more than one score are flagged and not counted.
+pub async fn process_reaction_rating(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionRatingParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+
+    let emojis = parameter.emojis.iter().map(|score| score.emoji.as_str());
+    let report_parameter = ReactionUsersParameter {
+        is_reaction_grouping: true,
+        is_show_count: true,
+        user_filter: UserFilter {
+            exclude_bots: true,
+            exclude_user: Some(parameter.message.author.id),
+        },
+        ..ReactionUsersParameter::for_emojis(&parameter.message, emojis)
+    };
+    let report = build_reaction_report(ctx, &report_parameter).await?;
+    let sheet = to_rating_sheet(&report, &parameter.emojis);
+
+    Ok(ReactionUsersResponse::text(
+        get_reaction_users_header_text(&report),
+        format!("{}{}", get_rating_text(&sheet), get_reaction_failures_text(&report)),
+    ))
+}
+
+/// Reads the reactions of `report` as the scores of `emojis`.
+pub fn to_rating_sheet(report: &ReactionReport, emojis: &[ScoreEmoji]) -> RatingSheet {
+    let matchers: Vec<(ReactionMatcher, i64)> = emojis
+        .iter()
+        .map(|score| (ReactionMatcher::parse(&score.emoji), score.score))
+        .collect();
+
+    let mut rows: Vec<(String, i64)> = Vec::new();
+    let mut order: Vec<&User> = Vec::new();
+    let mut chosen: HashMap<UserId, (Vec<i64>, Vec<String>)> = HashMap::new();
+
+    for reaction in &report.reactions {
+        let Some(score) = matchers
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(&reaction.reaction_type))
+            .map(|(_, score)| *score)
+        else {
+            continue;
+        };
+        match rows.iter_mut().find(|(_, row_score)| *row_score == score) {
+            Some((label, _)) => {
+                label.push(' ');
+                label.push_str(&reaction.emoji);
+            }
+            None => rows.push((reaction.emoji.clone(), score)),
+        }
+
+        for user in &reaction.users {
+            let (scores, user_emojis) = chosen.entry(user.id).or_insert_with(|| {
+                order.push(user);
+                (Vec::new(), Vec::new())
+            });
+            if !scores.contains(&score) {
+                scores.push(score);
+            }
+            user_emojis.push(reaction.emoji.clone());
+        }
+    }
+    rows.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    let mut sheet = RatingSheet { rows, ..RatingSheet::default() };
+    for user in order {
+        let (scores, user_emojis) = &chosen[&user.id];
+        match scores.as_slice() {
+            [score] => sheet.scores.push((user.clone(), *score)),
+            _ => sheet.multiple.push((user.clone(), user_emojis.clone())),
+        }
+    }
+    sheet
+}
+
+/// Shows the summary, the distribution and the users of each score.
+pub fn get_rating_text(sheet: &RatingSheet) -> String {
+    let format_stat = |value: Option<f64>| value.map(|value| format!("{:.2}", value)).unwrap_or_else(|| "-".to_string());
+
+    let multiple_note = if sheet.multiple.is_empty() {
+        String::new()
+    } else {
+        format!(" ({} with multiple scores, not counted)", sheet.multiple.len())
+    };
+
+    let counts: Vec<usize> = sheet.rows.iter().map(|(_, score)| sheet.users_with(*score).len()).collect();
+    let max_count = counts.iter().copied().max().unwrap_or(0);
+    let total = sheet.scores.len();
+
+    let distribution: String = sheet.rows
+        .iter()
+        .zip(&counts)
+        .map(|((emoji, score), count)| {
+            let bar_len = (count * HISTOGRAM_WIDTH).checked_div(max_count).unwrap_or(0);
+            let percent = (count * 100).checked_div(total).unwrap_or(0);
+            format!("  {} {:>3} | {} {} ({}%)\n", emoji, score, "█".repeat(bar_len), count, percent)
+        })
+        .collect();
+
+    let users: String = sheet.rows
+        .iter()
+        .map(|(emoji, score)| {
+            let users = sheet.users_with(*score);
+            if users.is_empty() {
+                format!("  {}: -\n", emoji)
+            } else {
+                let mentions = to_mentions(&users);
+                format!("  {}: {}```{}```\n", emoji, mentions, mentions)
+            }
+        })
+        .collect();
+
+    let multiple = if sheet.multiple.is_empty() {
+        String::new()
+    } else {
+        let users: Vec<User> = sheet.multiple.iter().map(|(user, _)| user.clone()).collect();
+        let details = sheet.multiple
+            .iter()
+            .map(|(user, user_emojis)| format!("{} ({})", user.name, user_emojis.join(" ")))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("  ⚠️ Multiple scores: {}  {}\n", to_mentions(&users), details)
+    };
+
+    format!(
+        "Rating:\n  Responses: {}{}\n  Average: {}\n  Median: {}\n\nDistribution:\n{}\nScores:\n{}{}",
+        total,
+        multiple_note,
+        format_stat(sheet.average()),
+        format_stat(sheet.median()),
+        distribution,
+        users,
+        multiple,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+
+    fn report() -> ReactionReport {
+        let fetched = vec![
+            fetched("5\u{fe0f}\u{20e3}", vec![user(10, "alice"), user(11, "bob")]),
+            fetched("4\u{fe0f}\u{20e3}", vec![user(12, "carol"), user(11, "bob")]),
+            fetched("3\u{fe0f}\u{20e3}", vec![]),
+            fetched("2\u{fe0f}\u{20e3}", vec![user(13, "dave")]),
+            fetched("1\u{fe0f}\u{20e3}", vec![user(14, "erin")]),
+        ];
+        ReactionReport::new(&message(user(1, "author")), String::new(), fetched, false)
+    }
+
+    #[test]
+    fn test_rating_sheet_flags_multiple_scores() {
+        let sheet = to_rating_sheet(&report(), &default_score_emojis());
+
+        let scores: Vec<(&str, i64)> = sheet.scores.iter().map(|(user, score)| (user.name.as_str(), *score)).collect();
+        assert_eq!(scores, vec![("alice", 5), ("carol", 4), ("dave", 2), ("erin", 1)]);
+        assert_eq!(sheet.multiple.len(), 1);
+        assert_eq!(sheet.multiple[0].0.name, "bob");
+        assert_eq!(sheet.rows.iter().map(|(_, score)| *score).collect::<Vec<i64>>(), vec![5, 4, 3, 2, 1]);
+        assert_eq!(sheet.average(), Some(3.0));
+        assert_eq!(sheet.median(), Some(3.0));
+    }
+
+    #[test]
+    fn test_rating_text() {
+        let text = get_rating_text(&to_rating_sheet(&report(), &default_score_emojis()));
+
+        assert!(text.contains("Responses: 4 (1 with multiple scores, not counted)"));
+        assert!(text.contains("Average: 3.00"));
+        assert!(text.contains("5\u{fe0f}\u{20e3}   5 | ████████████████████ 1 (25%)"));
+        assert!(text.contains("3\u{fe0f}\u{20e3}   3 |  0 (0%)"));
+        assert!(text.contains("bob (5\u{fe0f}\u{20e3} 4\u{fe0f}\u{20e3})"));
+    }
+
+    #[test]
+    fn test_emojis_with_the_same_score_share_a_row() {
+        let fetched = vec![
+            fetched("👍", vec![user(10, "alice"), user(11, "bob")]),
+            fetched("❤\u{fe0f}", vec![user(12, "carol"), user(11, "bob")]),
+            fetched("👎", vec![user(13, "dave")]),
+        ];
+        let report = ReactionReport::new(&message(user(1, "author")), String::new(), fetched, false);
+        let emojis = parse_score_emojis("👍=1, ❤\u{fe0f}=1, 👎=0").unwrap();
+
+        let sheet = to_rating_sheet(&report, &emojis);
+        let text = get_rating_text(&sheet);
+
+        assert_eq!(sheet.rows, vec![("👍 ❤\u{fe0f}".to_string(), 1), ("👎".to_string(), 0)]);
+        assert!(sheet.multiple.is_empty());
+        assert!(text.contains("Responses: 4\n"));
+        assert!(text.contains("👍 ❤\u{fe0f}   1 | ████████████████████ 3 (75%)"));
+        assert!(text.contains("👎   0 | ██████ 1 (25%)"));
+    }
+
+    #[test]
+    fn test_median_odd_count() {
+        let mut sheet = RatingSheet::default();
+        for (id, score) in [(1, 2), (2, 9), (3, 4)] {
+            sheet.scores.push((user(id, "u"), score));
+        }
+        assert_eq!(sheet.median(), Some(4.0));
+        assert_eq!(RatingSheet::default().average(), None);
+    }
+
+    #[test]
+    fn test_parse_score_emojis() {
+        let emojis = parse_score_emojis("😡=1, ⭐ = 5,, <:meh:123>=-1").unwrap();
+
+        assert_eq!(emojis.len(), 3);
+        assert_eq!(emojis[1], ScoreEmoji { emoji: "⭐".to_string(), score: 5 });
+        assert_eq!(emojis[2].score, -1);
+        assert!(parse_score_emojis("⭐").is_err());
+        assert!(parse_score_emojis("⭐=high").is_err());
+        assert!(parse_score_emojis("⭐=1000001").is_err());
+        assert!(parse_score_emojis("⭐=-9223372036854775808").is_err());
+        assert_eq!(parse_score_emojis("⭐=-1000000").unwrap()[0].score, -MAX_SCORE);
+        assert!(parse_score_emojis(" , ").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use poise::serenity_prelude::{Mentionable, Message, UserId};
+
+use crate::services::reaction_snapshot::{Snapshot, SnapshotReaction};
+use crate::services::reaction_users::filter::ReactionFilter;
+use crate::services::reaction_users::report::FetchedReaction;
+use crate::services::reaction_users::types::ReactionUsersResponse;
+use crate::services::reaction_users::utils::fetch_reactions;
+
+#[derive(Debug)]
+pub struct ReactionDiffParameter {
+    pub message: Message,
+    /// Snapshot to compare with; the latest snapshot of the message when `None`
+    pub since_snapshot: Option<i64>,
+}
+
+/// Users who added or removed one reaction since the snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmojiDiff {
+    pub emoji: String,
+    pub added: Vec<UserId>,
+    pub removed: Vec<UserId>,
+}
+
+/// Changes between a snapshot and the current reactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactionDiff {
+    /// One entry per emoji, current reactions first, then those that are gone
+    pub emojis: Vec<EmojiDiff>,
+    /// Users who had no reaction in the snapshot and have one now
+    pub joined: Vec<UserId>,
+    /// Users who had a reaction in the snapshot and have none now
+    pub dropped: Vec<UserId>,
+    /// Emojis whose current users could not be fetched, with the reason.
+    /// They are left out of `emojis`, `joined` and `dropped`.
+    pub uncompared: Vec<(String, String)>,
+}
+
+/// Compares the current reactions of the message with a saved snapshot.
+///
+/// # Errors
+/// Returns an error when there is no snapshot to compare with, or when the given
+/// snapshot belongs to another message or server.
+pub async fn process_reaction_diff(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionDiffParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+    let store = &ctx.data().snapshots;
+    let message = &parameter.message;
+
+    let snapshot_id = match parameter.since_snapshot {
+        Some(id) => id,
+        None => store
+            .latest_for_diff(ctx.guild_id(), ctx.author().id, message.id)?
+            .ok_or("This message has no snapshots yet. Save one with `/reaction_snapshot save`.")?,
+    };
+    let snapshot = store
+        .get(snapshot_id)?
+        .filter(|snapshot| snapshot.is_visible_to(ctx.guild_id(), ctx.author().id))
+        .ok_or_else(|| format!("There is no snapshot #{} here.", snapshot_id))?;
+    if snapshot.message_id != message.id {
+        return Err(format!("Snapshot #{} was saved for another message: {}", snapshot.id, snapshot.message_url()).into());
+    }
+
+    // スナップショットと同じく、フィルタせずに全てのリアクションを取得
+    let current = fetch_reactions(ctx.http(), message, &ReactionFilter::default()).await;
+    let diff = diff_reactions(&snapshot.reactions, &current);
+
+    Ok(ReactionUsersResponse::text(
+        format!("🔍 Changes since snapshot {}\n", to_snapshot_title(&snapshot)),
+        get_reaction_diff_text(&diff),
+    ))
+}
+
+/// Compares the users of each emoji, keyed by `reaction_type.to_string()`.
+///
+/// Emojis whose users could not be fetched are not compared, so that their users
+/// are not reported as removed.
+pub fn diff_reactions(before: &[SnapshotReaction], fetched: &[FetchedReaction]) -> ReactionDiff {
+    let mut after: Vec<(String, Vec<UserId>)> = Vec::new();
+    let mut uncompared = Vec::new();
+    for reaction in fetched {
+        match &reaction.users {
+            Ok(users) => after.push((reaction.reaction_type.to_string(), users.iter().map(|user| user.id).collect())),
+            Err(e) => uncompared.push((reaction.reaction_type.to_string(), e.clone())),
+        }
+    }
+    let is_uncompared = |emoji: &str| uncompared.iter().any(|(failed, _)| failed == emoji);
+    let before: Vec<&SnapshotReaction> = before.iter().filter(|reaction| !is_uncompared(&reaction.emoji)).collect();
+
+    let before_users = |emoji: &str| -> Vec<UserId> {
+        before
+            .iter()
+            .find(|reaction| reaction.emoji == emoji)
+            .map(|reaction| reaction.user_ids.clone())
+            .unwrap_or_default()
+    };
+
+    let mut emojis: Vec<EmojiDiff> = after
+        .iter()
+        .map(|(emoji, user_ids)| to_emoji_diff(emoji, &before_users(emoji), user_ids))
+        .collect();
+    emojis.extend(
+        before
+            .iter()
+            .filter(|reaction| !after.iter().any(|(emoji, _)| *emoji == reaction.emoji))
+            .map(|reaction| to_emoji_diff(&reaction.emoji, &reaction.user_ids, &[])),
+    );
+
+    let before_all: Vec<UserId> = before.iter().flat_map(|reaction| reaction.user_ids.iter().copied()).collect();
+    let after_all: Vec<UserId> = after.iter().flat_map(|(_, user_ids)| user_ids.iter().copied()).collect();
+
+    ReactionDiff {
+        emojis,
+        joined: difference(&after_all, &before_all),
+        dropped: difference(&before_all, &after_all),
+        uncompared,
+    }
+}
+
+pub fn get_reaction_diff_text(diff: &ReactionDiff) -> String {
+    let emojis: String = diff.emojis
+        .iter()
+        .map(|emoji| {
+            if emoji.added.is_empty() && emoji.removed.is_empty() {
+                return format!("  {}: no change\n", emoji.emoji);
+            }
+            let mut text = format!("  {}: +{} -{}\n", emoji.emoji, emoji.added.len(), emoji.removed.len());
+            if !emoji.added.is_empty() {
+                text.push_str(&format!("    Added: {}\n", to_mentions(&emoji.added)));
+            }
+            if !emoji.removed.is_empty() {
+                text.push_str(&format!("    Removed: {}\n", to_mentions(&emoji.removed)));
+            }
+            text
+        })
+        .collect();
+
+    let overall = |users: &[UserId]| if users.is_empty() { "-".to_string() } else { to_mentions(users) };
+    let uncompared: String = diff.uncompared
+        .iter()
+        .map(|(emoji, reason)| format!("\n⚠️ Could not compare {}: {}", emoji, reason))
+        .collect();
+
+    format!(
+        "Reactions:\n{}\nOverall:\n  Joined ({}): {}\n  Dropped out ({}): {}\n{}",
+        if emojis.is_empty() { "  No reactions.\n".to_string() } else { emojis },
+        diff.joined.len(),
+        overall(&diff.joined),
+        diff.dropped.len(),
+        overall(&diff.dropped),
+        uncompared,
+    )
+}
+
+fn to_emoji_diff(emoji: &str, before: &[UserId], after: &[UserId]) -> EmojiDiff {
+    EmojiDiff {
+        emoji: emoji.to_string(),
+        added: difference(after, before),
+        removed: difference(before, after),
+    }
+}
+
+/// Users of `a` that are not in `b`, deduplicated, in the order of `a`
+fn difference(a: &[UserId], b: &[UserId]) -> Vec<UserId> {
+    let b: HashSet<&UserId> = b.iter().collect();
+    let mut seen = HashSet::new();
+    a.iter().filter(|id| !b.contains(id) && seen.insert(**id)).copied().collect()
+}
+
+fn to_mentions(user_ids: &[UserId]) -> String {
+    user_ids.iter().map(|id| id.mention().to_string()).collect::<Vec<String>>().join(" ")
+}
+
+fn to_snapshot_title(snapshot: &Snapshot) -> String {
+    format!(
+        "#{}{} (<t:{}:f>)\n  📝: {}",
+        snapshot.id,
+        snapshot.label.as_deref().map(|label| format!(" {}", label)).unwrap_or_default(),
+        snapshot.fetched_at,
+        snapshot.message_url(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, user};
+
+    fn before() -> Vec<SnapshotReaction> {
+        let ids = |ids: &[u64]| ids.iter().map(|&id| UserId::new(id)).collect();
+        vec![
+            SnapshotReaction { emoji: "✅".to_string(), user_ids: ids(&[10, 11, 12]) },
+            SnapshotReaction { emoji: "🎉".to_string(), user_ids: ids(&[13]) },
+        ]
+    }
+
+    #[test]
+    fn test_diff_reactions() {
+        let after = vec![
+            fetched("✅", vec![user(10, "a"), user(14, "e")]),
+            fetched("❌", vec![user(11, "b")]),
+        ];
+
+        let diff = diff_reactions(&before(), &after);
+
+        assert_eq!(diff.emojis.iter().map(|emoji| emoji.emoji.as_str()).collect::<Vec<&str>>(), vec!["✅", "❌", "🎉"]);
+        assert_eq!(diff.emojis[0].added, vec![UserId::new(14)]);
+        assert_eq!(diff.emojis[0].removed, vec![UserId::new(11), UserId::new(12)]);
+        assert_eq!(diff.emojis[1].added, vec![UserId::new(11)]);
+        assert_eq!(diff.emojis[2].removed, vec![UserId::new(13)]);
+        assert_eq!(diff.joined, vec![UserId::new(14)]);
+        assert_eq!(diff.dropped, vec![UserId::new(12), UserId::new(13)]);
+    }
+
+    #[test]
+    fn test_diff_text() {
+        let after = vec![
+            fetched("✅", vec![user(10, "a"), user(11, "b"), user(12, "c")]),
+            fetched("🎉", vec![]),
+        ];
+
+        let text = get_reaction_diff_text(&diff_reactions(&before(), &after));
+
+        assert!(text.contains("  ✅: no change\n"));
+        assert!(text.contains("  🎉: +0 -1\n    Removed: <@13>\n"));
+        assert!(text.contains("Joined (0): -"));
+        assert!(text.contains("Dropped out (1): <@13>"));
+    }
+
+    #[test]
+    fn test_failed_emoji_is_not_compared() {
+        let mut failed = fetched("🎉", Vec::new());
+        failed.users = Err("Missing Access".to_string());
+        let after = vec![fetched("✅", vec![user(10, "a"), user(11, "b"), user(12, "c")]), failed];
+
+        let diff = diff_reactions(&before(), &after);
+
+        assert_eq!(diff.emojis.len(), 1);
+        assert!(diff.dropped.is_empty());
+        assert_eq!(diff.uncompared, vec![("🎉".to_string(), "Missing Access".to_string())]);
+        assert!(get_reaction_diff_text(&diff).ends_with("\n⚠️ Could not compare 🎉: Missing Access"));
+    }
+}
@@ -0,0 +1,296 @@
+use poise::serenity_prelude::{ChannelId, GuildId, Mentionable, Message, MessageId, Timestamp, UserId};
+
+use crate::services::reaction_users::filter::ReactionFilter;
+use crate::services::reaction_users::renderers::text::get_reaction_failures_text;
+use crate::services::reaction_users::report::{FetchedReaction, ReactionReport};
+use crate::services::reaction_users::utils::fetch_reactions;
+use crate::utils::discord_helper::make_message_url;
+
+pub mod diff;
+pub mod store;
+
+/// Number of snapshots shown by `/reaction_snapshot list`.
+pub const SNAPSHOT_LIST_LIMIT: usize = 20;
+
+/// Number of automatic snapshots kept per message; older ones are deleted as new ones are saved.
+pub const AUTO_SNAPSHOTS_PER_MESSAGE: usize = 10;
+
+/// The reactors of a message at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Assigned by the database; ignored when saving
+    pub id: i64,
+    pub message_id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    /// Free text to find the snapshot again, e.g. "sign-ups closed"
+    pub label: Option<String>,
+    /// The user who saved the snapshot
+    pub created_by: UserId,
+    /// When the reactions were fetched, in seconds since the Unix epoch
+    pub fetched_at: i64,
+    /// Users of each reaction, in Discord's reaction order
+    pub reactions: Vec<SnapshotReaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotReaction {
+    /// `reaction_type.to_string()`, as in [`ReactionReport`]
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
+}
+
+/// A line of `/reaction_snapshot list`.
+#[derive(Debug, Clone)]
+pub struct SnapshotSummary {
+    pub id: i64,
+    pub message_id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub label: Option<String>,
+    pub fetched_at: i64,
+    /// Number of distinct users over all reactions
+    pub user_count: usize,
+}
+
+impl Snapshot {
+    /// Takes the reactions of `report` as they are.
+    pub fn from_report(report: &ReactionReport, created_by: UserId, label: Option<String>, fetched_at: i64) -> Self {
+        Self {
+            id: 0,
+            message_id: report.message.id,
+            channel_id: report.message.channel_id,
+            guild_id: report.message.guild_id,
+            label,
+            created_by,
+            fetched_at,
+            reactions: report.reactions
+                .iter()
+                .map(|reaction| SnapshotReaction {
+                    emoji: reaction.emoji.clone(),
+                    user_ids: reaction.users.iter().map(|user| user.id).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Takes the reactions whose users could be fetched, before any filter is applied.
+    pub fn from_fetched(
+        message: &Message,
+        fetched: &[FetchedReaction],
+        created_by: UserId,
+        label: Option<String>,
+        fetched_at: i64,
+    ) -> Self {
+        Self {
+            id: 0,
+            message_id: message.id,
+            channel_id: message.channel_id,
+            guild_id: message.guild_id,
+            label,
+            created_by,
+            fetched_at,
+            reactions: fetched
+                .iter()
+                .filter_map(|reaction| {
+                    let users = reaction.users.as_ref().ok()?;
+                    Some(SnapshotReaction {
+                        emoji: reaction.reaction_type.to_string(),
+                        user_ids: users.iter().map(|user| user.id).collect(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn message_url(&self) -> String {
+        to_message_url(self.guild_id, self.channel_id, self.message_id)
+    }
+
+    /// Whether `viewer` may see the snapshot from `guild_id`.
+    ///
+    /// Snapshots are shared within their server; outside servers only their creator sees them.
+    pub fn is_visible_to(&self, guild_id: Option<GuildId>, viewer: UserId) -> bool {
+        self.guild_id == guild_id && (guild_id.is_some() || self.created_by == viewer)
+    }
+}
+
+/// Fetches every reaction of the message with all of its users and saves them.
+///
+/// No filters are applied, so that later comparisons see the complete picture.
+/// `guild_id` is the server the command runs in, since messages fetched over HTTP
+/// do not carry one.
+///
+/// # Errors
+/// Returns an error when the reactions cannot be fetched or the snapshot cannot be saved.
+/// Reactions whose users could not be fetched are left out and noted in the reply.
+pub async fn process_snapshot_save(
+    ctx: crate::Context<'_>,
+    message: &Message,
+    label: Option<String>,
+) -> Result<String, crate::Error> {
+
+    let mut message = message.clone();
+    message.guild_id = message.guild_id.or(ctx.guild_id());
+
+    // レポートの自動保存と二重にならないよう、直接取得する
+    let fetched = fetch_reactions(ctx.http(), &message, &ReactionFilter::default()).await;
+    let report = ReactionReport::new(&message, make_message_url(&message).await, fetched, false);
+
+    let snapshot = Snapshot::from_report(&report, ctx.author().id, label, Timestamp::now().unix_timestamp());
+    let id = ctx.data().snapshots.save(&snapshot)?;
+
+    Ok(format!(
+        "📸 Saved snapshot #{} of {}: {} reactions, {} users{}",
+        id,
+        report.message.url,
+        report.reactions.len(),
+        report.user_count(),
+        get_reaction_failures_text(&report),
+    ))
+}
+
+/// Saves the reactions fetched for a report, labelled with the command that built it.
+///
+/// Called before the report's filters are applied, so the snapshot holds every user of
+/// the fetched reactions. Nothing is saved when the reactions did not change since the
+/// latest snapshot of the message, and only [`AUTO_SNAPSHOTS_PER_MESSAGE`] are kept.
+///
+/// The database is written on a blocking thread that the report does not wait for;
+/// a failure is only logged, so that the report is still sent.
+pub fn save_report_snapshot(ctx: crate::Context<'_>, message: &Message, fetched: &[FetchedReaction]) {
+    let mut message = message.clone();
+    message.guild_id = message.guild_id.or(ctx.guild_id());
+
+    let snapshot = Snapshot::from_fetched(
+        &message,
+        fetched,
+        ctx.author().id,
+        Some(format!("auto: {}", ctx.command().qualified_name)),
+        Timestamp::now().unix_timestamp(),
+    );
+    let store = ctx.data().snapshots.clone();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = store.save_auto(&snapshot, AUTO_SNAPSHOTS_PER_MESSAGE) {
+            log::warn!("Failed to save the snapshot of message {}: {}", snapshot.message_id, e);
+        }
+    });
+}
+
+/// Shows a snapshot like the grouped text report, with the time it was taken.
+pub fn to_snapshot_text(snapshot: &Snapshot) -> String {
+    let reactions: String = snapshot.reactions
+        .iter()
+        .map(|reaction| {
+            let mentions = reaction.user_ids
+                .iter()
+                .map(|id| id.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            format!("  {}: {:>4}: {}```{}```\n", reaction.emoji, reaction.user_ids.len(), mentions, mentions)
+        })
+        .collect();
+
+    format!(
+        "📸 Snapshot #{}{}\n  📝: {}\n  🕒: <t:{}:f>\n  👤: {}\n\nReactions:\n{}",
+        snapshot.id,
+        snapshot.label.as_deref().map(|label| format!(" ({})", label)).unwrap_or_default(),
+        snapshot.message_url(),
+        snapshot.fetched_at,
+        snapshot.created_by.mention(),
+        if reactions.is_empty() { "No one reacted.\n".to_string() } else { reactions },
+    )
+}
+
+pub fn to_snapshot_list_text(summaries: &[SnapshotSummary]) -> String {
+    if summaries.is_empty() {
+        return "No snapshots yet. Save one with `/reaction_snapshot save`.".to_string();
+    }
+
+    let lines: String = summaries
+        .iter()
+        .map(|summary| {
+            format!(
+                "- #{}{} <t:{}:f>, {} users: {}\n",
+                summary.id,
+                summary.label.as_deref().map(|label| format!(" **{}**", label)).unwrap_or_default(),
+                summary.fetched_at,
+                summary.user_count,
+                to_message_url(summary.guild_id, summary.channel_id, summary.message_id),
+            )
+        })
+        .collect();
+    format!("📸 Snapshots (latest {}):\n{}", SNAPSHOT_LIST_LIMIT, lines)
+}
+
+fn to_message_url(guild_id: Option<GuildId>, channel_id: ChannelId, message_id: MessageId) -> String {
+    format!(
+        "https://discord.com/channels/{}/{}/{}",
+        guild_id.map(|id| id.to_string()).unwrap_or_else(|| "@me".to_string()),
+        channel_id,
+        message_id,
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+
+    pub(crate) fn snapshot(label: Option<&str>, fetched_at: i64) -> Snapshot {
+        let report = ReactionReport::new(
+            &message(user(1, "author")),
+            String::new(),
+            vec![
+                fetched("✅", vec![user(10, "a"), user(11, "b")]),
+                fetched("❌", vec![user(12, "c"), user(10, "a")]),
+            ],
+            false,
+        );
+        Snapshot::from_report(&report, UserId::new(1), label.map(str::to_string), fetched_at)
+    }
+
+    #[test]
+    fn test_snapshot_text() {
+        let mut snapshot = snapshot(Some("closed"), 1_700_000_000);
+        snapshot.id = 7;
+
+        let text = to_snapshot_text(&snapshot);
+
+        assert!(text.starts_with("📸 Snapshot #7 (closed)\n  📝: https://discord.com/channels/100/200/300"));
+        assert!(text.contains("<t:1700000000:f>"));
+        assert!(text.contains("  ✅:    2: <@10> <@11>```<@10> <@11>```"));
+    }
+
+    #[test]
+    fn test_from_fetched_skips_failures() {
+        let message = message(user(1, "author"));
+        let mut failed = fetched("❌", Vec::new());
+        failed.users = Err("Missing Access".to_string());
+
+        let snapshot = Snapshot::from_fetched(
+            &message,
+            &[fetched("✅", vec![user(10, "a"), user(11, "b")]), failed],
+            UserId::new(1),
+            None,
+            0,
+        );
+
+        assert_eq!(snapshot.reactions.len(), 1);
+        assert_eq!(snapshot.reactions[0].emoji, "✅");
+        assert_eq!(snapshot.reactions[0].user_ids, vec![UserId::new(10), UserId::new(11)]);
+    }
+
+    #[test]
+    fn test_is_visible_to() {
+        let mut snapshot = snapshot(None, 0);
+
+        assert!(snapshot.is_visible_to(Some(GuildId::new(100)), UserId::new(2)));
+        assert!(!snapshot.is_visible_to(Some(GuildId::new(101)), UserId::new(1)));
+        assert!(!snapshot.is_visible_to(None, UserId::new(1)));
+
+        snapshot.guild_id = None;
+        assert!(snapshot.is_visible_to(None, UserId::new(1)));
+        assert!(!snapshot.is_visible_to(None, UserId::new(2)));
+    }
+}
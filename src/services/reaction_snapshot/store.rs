@@ -0,0 +1,379 @@
+use std::path::Path;
+use std::sync::Mutex;
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId, UserId};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::services::reaction_snapshot::{Snapshot, SnapshotReaction, SnapshotSummary};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS snapshots (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    message_id  INTEGER NOT NULL,
+    channel_id  INTEGER NOT NULL,
+    guild_id    INTEGER,
+    label       TEXT,
+    created_by  INTEGER NOT NULL,
+    fetched_at  INTEGER NOT NULL,
+    is_auto     INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS snapshots_message ON snapshots (message_id, id);
+CREATE TABLE IF NOT EXISTS snapshot_reactions (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    emoji       TEXT NOT NULL,
+    PRIMARY KEY (snapshot_id, position)
+);
+CREATE TABLE IF NOT EXISTS snapshot_users (
+    snapshot_id INTEGER NOT NULL,
+    position    INTEGER NOT NULL,
+    user_order  INTEGER NOT NULL,
+    user_id     INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, position, user_order),
+    FOREIGN KEY (snapshot_id, position) REFERENCES snapshot_reactions (snapshot_id, position) ON DELETE CASCADE
+);
+"#;
+
+/// Reaction snapshots kept in a local SQLite database.
+///
+/// Discord IDs are stored as SQLite integers; snowflakes fit in 63 bits.
+/// Queries are short, so the connection is used from async code behind a mutex.
+pub struct SnapshotStore {
+    connection: Mutex<Connection>,
+}
+
+impl std::fmt::Debug for SnapshotStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotStore").finish_non_exhaustive()
+    }
+}
+
+impl SnapshotStore {
+    /// Opens (or creates) the database at `path` and its tables.
+    pub fn open(path: &Path) -> Result<Self, crate::Error> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, crate::Error> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Self, crate::Error> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+
+        // 自動保存の列が無いデータベースには列を追加し、ラベルで自動保存だったものを区別する
+        if connection.prepare("SELECT is_auto FROM snapshots LIMIT 0").is_err() {
+            connection.execute_batch(
+                "ALTER TABLE snapshots ADD COLUMN is_auto INTEGER NOT NULL DEFAULT 0;
+                 UPDATE snapshots SET is_auto = 1 WHERE label LIKE 'auto: %';",
+            )?;
+        }
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    /// Saves `snapshot` and returns its new ID. `snapshot.id` is ignored.
+    pub fn save(&self, snapshot: &Snapshot) -> Result<i64, crate::Error> {
+        let mut connection = self.connection.lock().map_err(|_| "The snapshot database is unavailable.")?;
+        let transaction = connection.transaction()?;
+        let id = insert(&transaction, snapshot, false)?;
+        transaction.commit()?;
+        Ok(id)
+    }
+
+    /// Saves a snapshot taken automatically for a report, and returns its new ID.
+    ///
+    /// Nothing is saved, and `None` is returned, when the reactions are the same as in the
+    /// latest snapshot of the message. Only the newest `keep` automatic snapshots of the
+    /// message are kept; snapshots saved with `/reaction_snapshot save` are never removed.
+    pub fn save_auto(&self, snapshot: &Snapshot, keep: usize) -> Result<Option<i64>, crate::Error> {
+        let mut connection = self.connection.lock().map_err(|_| "The snapshot database is unavailable.")?;
+        let transaction = connection.transaction()?;
+
+        let latest = latest_id(&transaction, snapshot.guild_id, snapshot.created_by, snapshot.message_id, false)?;
+        if let Some(latest) = latest
+            && get(&transaction, latest)?.is_some_and(|latest| latest.reactions == snapshot.reactions)
+        {
+            return Ok(None);
+        }
+
+        let id = insert(&transaction, snapshot, true)?;
+        transaction.execute(
+            "DELETE FROM snapshots
+             WHERE is_auto = 1 AND message_id = ?1 AND id NOT IN (
+                 SELECT id FROM snapshots WHERE is_auto = 1 AND message_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![to_sql_id(snapshot.message_id.get()), keep as i64],
+        )?;
+
+        transaction.commit()?;
+        Ok(Some(id))
+    }
+
+    /// Loads the snapshot with `id`, or `None` when there is none.
+    pub fn get(&self, id: i64) -> Result<Option<Snapshot>, crate::Error> {
+        let connection = self.connection.lock().map_err(|_| "The snapshot database is unavailable.")?;
+        get(&connection, id)
+    }
+
+    /// The ID of the snapshot of the message to compare with by default, visible as in [`Self::list`].
+    ///
+    /// The latest snapshot saved with `/reaction_snapshot save` is preferred over automatic ones,
+    /// so that it stays the reference however often reports are run.
+    pub fn latest_for_diff(
+        &self,
+        guild_id: Option<GuildId>,
+        viewer: UserId,
+        message_id: MessageId,
+    ) -> Result<Option<i64>, crate::Error> {
+        let connection = self.connection.lock().map_err(|_| "The snapshot database is unavailable.")?;
+        latest_id(&connection, guild_id, viewer, message_id, true)
+    }
+
+    /// Lists the latest snapshots of a server, newest first.
+    ///
+    /// Outside servers (`guild_id` is `None`) only the snapshots saved by `viewer` are listed,
+    /// so that DMs of different users stay apart.
+    /// `message_id` restricts the list to the snapshots of one message.
+    pub fn list(
+        &self,
+        guild_id: Option<GuildId>,
+        viewer: UserId,
+        message_id: Option<MessageId>,
+        limit: usize,
+    ) -> Result<Vec<SnapshotSummary>, crate::Error> {
+        let connection = self.connection.lock().map_err(|_| "The snapshot database is unavailable.")?;
+
+        let mut statement = connection.prepare(
+            "SELECT s.id, s.message_id, s.channel_id, s.label, s.fetched_at,
+                    (SELECT COUNT(DISTINCT u.user_id) FROM snapshot_users u WHERE u.snapshot_id = s.id)
+             FROM snapshots s
+             WHERE s.guild_id IS ?1 AND (?1 IS NOT NULL OR s.created_by = ?4)
+               AND (?2 IS NULL OR s.message_id = ?2)
+             ORDER BY s.id DESC
+             LIMIT ?3",
+        )?;
+        let summaries = statement
+            .query_map(
+                params![
+                    guild_id.map(|id| to_sql_id(id.get())),
+                    message_id.map(|id| to_sql_id(id.get())),
+                    limit as i64,
+                    to_sql_id(viewer.get()),
+                ],
+                |row| {
+                    Ok(SnapshotSummary {
+                        id: row.get(0)?,
+                        message_id: MessageId::new(from_sql_id(row.get(1)?)),
+                        channel_id: ChannelId::new(from_sql_id(row.get(2)?)),
+                        guild_id,
+                        label: row.get(3)?,
+                        fetched_at: row.get(4)?,
+                        user_count: row.get::<_, i64>(5)? as usize,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<SnapshotSummary>, rusqlite::Error>>()?;
+
+        Ok(summaries)
+    }
+}
+
+/// Inserts `snapshot` with its reactions and returns its new ID.
+fn insert(connection: &Connection, snapshot: &Snapshot, is_auto: bool) -> Result<i64, crate::Error> {
+    connection.execute(
+        "INSERT INTO snapshots (message_id, channel_id, guild_id, label, created_by, fetched_at, is_auto)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            to_sql_id(snapshot.message_id.get()),
+            to_sql_id(snapshot.channel_id.get()),
+            snapshot.guild_id.map(|id| to_sql_id(id.get())),
+            snapshot.label,
+            to_sql_id(snapshot.created_by.get()),
+            snapshot.fetched_at,
+            is_auto,
+        ],
+    )?;
+    let id = connection.last_insert_rowid();
+
+    for (position, reaction) in snapshot.reactions.iter().enumerate() {
+        connection.execute(
+            "INSERT INTO snapshot_reactions (snapshot_id, position, emoji) VALUES (?1, ?2, ?3)",
+            params![id, position as i64, reaction.emoji],
+        )?;
+        for (user_order, user_id) in reaction.user_ids.iter().enumerate() {
+            connection.execute(
+                "INSERT INTO snapshot_users (snapshot_id, position, user_order, user_id) VALUES (?1, ?2, ?3, ?4)",
+                params![id, position as i64, user_order as i64, to_sql_id(user_id.get())],
+            )?;
+        }
+    }
+    Ok(id)
+}
+
+fn get(connection: &Connection, id: i64) -> Result<Option<Snapshot>, crate::Error> {
+    let snapshot = connection
+        .query_row(
+            "SELECT id, message_id, channel_id, guild_id, label, created_by, fetched_at
+             FROM snapshots WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(Snapshot {
+                    id: row.get(0)?,
+                    message_id: MessageId::new(from_sql_id(row.get(1)?)),
+                    channel_id: ChannelId::new(from_sql_id(row.get(2)?)),
+                    guild_id: row.get::<_, Option<i64>>(3)?.map(|id| GuildId::new(from_sql_id(id))),
+                    label: row.get(4)?,
+                    created_by: UserId::new(from_sql_id(row.get(5)?)),
+                    fetched_at: row.get(6)?,
+                    reactions: Vec::new(),
+                })
+            },
+        )
+        .optional()?;
+    let Some(mut snapshot) = snapshot else {
+        return Ok(None);
+    };
+
+    let mut reactions = connection.prepare(
+        "SELECT emoji FROM snapshot_reactions WHERE snapshot_id = ?1 ORDER BY position",
+    )?;
+    snapshot.reactions = reactions
+        .query_map(params![id], |row| Ok(SnapshotReaction { emoji: row.get(0)?, user_ids: Vec::new() }))?
+        .collect::<Result<Vec<SnapshotReaction>, rusqlite::Error>>()?;
+
+    let mut users = connection.prepare(
+        "SELECT position, user_id FROM snapshot_users WHERE snapshot_id = ?1 ORDER BY position, user_order",
+    )?;
+    let rows = users.query_map(params![id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+    for row in rows {
+        let (position, user_id) = row?;
+        if let Some(reaction) = snapshot.reactions.get_mut(position as usize) {
+            reaction.user_ids.push(UserId::new(from_sql_id(user_id)));
+        }
+    }
+
+    Ok(Some(snapshot))
+}
+
+/// The latest snapshot of the message visible to `viewer`, manual ones first with `is_manual_first`.
+fn latest_id(
+    connection: &Connection,
+    guild_id: Option<GuildId>,
+    viewer: UserId,
+    message_id: MessageId,
+    is_manual_first: bool,
+) -> Result<Option<i64>, crate::Error> {
+    let id = connection
+        .query_row(
+            "SELECT id FROM snapshots
+             WHERE guild_id IS ?1 AND (?1 IS NOT NULL OR created_by = ?2) AND message_id = ?3
+             ORDER BY CASE WHEN ?4 THEN is_auto ELSE 0 END, id DESC
+             LIMIT 1",
+            params![
+                guild_id.map(|id| to_sql_id(id.get())),
+                to_sql_id(viewer.get()),
+                to_sql_id(message_id.get()),
+                is_manual_first,
+            ],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+fn to_sql_id(id: u64) -> i64 {
+    id as i64
+}
+
+fn from_sql_id(id: i64) -> u64 {
+    id as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_snapshot::tests::snapshot;
+
+    #[test]
+    fn test_save_and_get() {
+        let store = SnapshotStore::open_in_memory().unwrap();
+
+        let id = store.save(&snapshot(Some("closed"), 1_700_000_000)).unwrap();
+        let loaded = store.get(id).unwrap().unwrap();
+
+        assert_eq!(loaded.id, id);
+        assert_eq!(loaded.label.as_deref(), Some("closed"));
+        assert_eq!(loaded.guild_id, Some(GuildId::new(100)));
+        assert_eq!(loaded.fetched_at, 1_700_000_000);
+        assert_eq!(loaded.reactions, snapshot(None, 0).reactions);
+        assert!(store.get(id + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_list_newest_first() {
+        let store = SnapshotStore::open_in_memory().unwrap();
+        let first = store.save(&snapshot(None, 1)).unwrap();
+        let second = store.save(&snapshot(Some("second"), 2)).unwrap();
+        let mut dm = snapshot(None, 3);
+        dm.guild_id = None;
+        store.save(&dm).unwrap();
+
+        let viewer = UserId::new(1);
+        let summaries = store.list(Some(GuildId::new(100)), viewer, None, 10).unwrap();
+        assert_eq!(summaries.iter().map(|summary| summary.id).collect::<Vec<i64>>(), vec![second, first]);
+        assert_eq!(summaries[0].user_count, 3);
+
+        assert_eq!(store.list(None, viewer, None, 10).unwrap().len(), 1);
+        assert!(store.list(Some(GuildId::new(100)), viewer, Some(MessageId::new(999)), 10).unwrap().is_empty());
+        assert_eq!(store.list(Some(GuildId::new(100)), viewer, None, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_list_keeps_dms_apart() {
+        let store = SnapshotStore::open_in_memory().unwrap();
+        let mut dm = snapshot(None, 1);
+        dm.guild_id = None;
+        store.save(&dm).unwrap();
+
+        assert_eq!(store.list(None, UserId::new(1), None, 10).unwrap().len(), 1);
+        assert!(store.list(None, UserId::new(2), None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_auto_skips_unchanged_and_keeps_newest() {
+        let store = SnapshotStore::open_in_memory().unwrap();
+        let manual = store.save(&snapshot(None, 1)).unwrap();
+
+        assert_eq!(store.save_auto(&snapshot(None, 2), 2).unwrap(), None);
+
+        let mut ids = Vec::new();
+        for fetched_at in 3..6 {
+            let mut changed = snapshot(None, fetched_at);
+            changed.reactions[0].user_ids.push(UserId::new(fetched_at as u64 + 100));
+            ids.push(store.save_auto(&changed, 2).unwrap().unwrap());
+        }
+
+        let viewer = UserId::new(1);
+        let summaries = store.list(Some(GuildId::new(100)), viewer, None, 10).unwrap();
+        assert_eq!(summaries.iter().map(|summary| summary.id).collect::<Vec<i64>>(), vec![ids[2], ids[1], manual]);
+        assert!(store.get(ids[0]).unwrap().is_none());
+
+        let message_id = snapshot(None, 0).message_id;
+        assert_eq!(store.latest_for_diff(Some(GuildId::new(100)), viewer, message_id).unwrap(), Some(manual));
+    }
+
+    #[test]
+    fn test_latest_for_diff_falls_back_to_auto() {
+        let store = SnapshotStore::open_in_memory().unwrap();
+        let message_id = snapshot(None, 0).message_id;
+        let viewer = UserId::new(1);
+        assert_eq!(store.latest_for_diff(Some(GuildId::new(100)), viewer, message_id).unwrap(), None);
+
+        let auto = store.save_auto(&snapshot(None, 1), 10).unwrap();
+        assert_eq!(store.latest_for_diff(Some(GuildId::new(100)), viewer, message_id).unwrap(), auto);
+    }
+}
@@ -0,0 +1,92 @@
+use poise::serenity_prelude::{Mentionable, Message, UserId};
+
+use crate::services::reaction_teams::teams::{split_teams, Team};
+use crate::services::reaction_users::build_reaction_report;
+use crate::services::reaction_users::filter::{ReactionMatcher, UserFilter};
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text,
+};
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+pub mod teams;
+
+#[derive(Debug)]
+pub struct ReactionTeamsParameter {
+    pub message: Message,
+    /// Number of teams to split into
+    pub team_count: usize,
+    /// Only users who reacted with this emoji play
+    pub emoji: Option<String>,
+    /// Users who reacted with this emoji lead a team each
+    pub captain_emoji: Option<String>,
+    /// Users who reacted with the same emoji stay on the same team
+    pub is_emoji_grouping: bool,
+    /// Seed of the split; a random seed is used when `None`
+    pub seed: Option<u64>,
+}
+
+/// Splits the users who reacted to the message into teams.
+///
+/// Bots never play. The reply shows the seed, so the same split can be produced
+/// again (see [`split_teams`]).
+///
+/// # Errors
+/// Returns an error when there are fewer players than teams.
+pub async fn process_reaction_teams(
+    ctx: crate::Context<'_>,
+    parameter: &ReactionTeamsParameter,
+) -> Result<ReactionUsersResponse, crate::Error> {
+
+    // 参加用と主将用の絵文字のリアクションを取得
+    let emojis = match &parameter.emoji {
+        Some(emoji) => vec![Some(emoji.as_str()), parameter.captain_emoji.as_deref()],
+        None => Vec::new(),
+    };
+    let report_parameter = ReactionUsersParameter {
+        user_filter: UserFilter { exclude_bots: true, exclude_user: None },
+        ..ReactionUsersParameter::for_emojis(&parameter.message, emojis.into_iter().flatten())
+    };
+    let report = build_reaction_report(ctx, &report_parameter).await?;
+
+    let captain_matcher = parameter.captain_emoji.as_deref().map(ReactionMatcher::parse);
+    let is_captain_reaction = |reaction_type| captain_matcher.as_ref().is_some_and(|matcher| matcher.is_match(reaction_type));
+
+    let mut captains: Vec<UserId> = Vec::new();
+    let mut groups: Vec<Vec<UserId>> = Vec::new();
+    for reaction in &report.reactions {
+        let user_ids = reaction.users.iter().map(|user| user.id);
+        if is_captain_reaction(&reaction.reaction_type) {
+            captains.extend(user_ids);
+        } else {
+            groups.push(user_ids.collect());
+        }
+    }
+
+    let player_count = report.user_count();
+    if player_count < parameter.team_count {
+        return Err(format!(
+            "Only {} players reacted, not enough for {} teams.",
+            player_count, parameter.team_count
+        ).into());
+    }
+
+    let seed = parameter.seed.unwrap_or_else(rand::random);
+    let teams = split_teams(&captains, &groups, parameter.is_emoji_grouping, parameter.team_count, seed);
+
+    let content = format!(
+        "🎮 Teams: {} teams of {} players\nSeed: `{}`\n\n{}{}",
+        teams.len(),
+        player_count,
+        seed,
+        teams.iter().enumerate().map(|(index, team)| to_team_text(index, team)).collect::<String>(),
+        get_reaction_failures_text(&report),
+    );
+
+    Ok(ReactionUsersResponse::text(get_reaction_users_header_text(&report), content))
+}
+
+fn to_team_text(index: usize, team: &Team) -> String {
+    let captain = team.captain.map(|id| format!("👑{} ", id.mention())).unwrap_or_default();
+    let members = team.members.iter().map(|id| id.mention().to_string()).collect::<Vec<String>>().join(" ");
+    format!("Team {} ({}): {}{}\n", index + 1, team.len(), captain, members)
+}
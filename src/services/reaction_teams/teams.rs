@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+use poise::serenity_prelude::UserId;
+
+use crate::services::reaction_draw::draw::{seeded_rng, shuffle_prefix};
+
+/// One team of a split. The captain, if any, is not part of `members`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Team {
+    pub captain: Option<UserId>,
+    pub members: Vec<UserId>,
+}
+
+impl Team {
+    pub fn len(&self) -> usize {
+        self.members.len() + usize::from(self.captain.is_some())
+    }
+}
+
+/// Splits the players into `team_count` teams, reproducibly from `seed`.
+///
+/// `groups` are players who stay on the same team; pass one group per player
+/// (or a single list, see below) to split freely. The algorithm is fixed so that
+/// anyone can re-run the split with the same seed:
+/// 1. Seed ChaCha20 with `ChaCha20Rng::seed_from_u64(seed)` ([`seeded_rng`]).
+/// 2. Sort the captains by user ID and shuffle them ([`shuffle_prefix`]). The first
+///    `team_count` lead teams 1, 2, ...; the others play as single players.
+/// 3. Each player belongs to the first group they appear in, captains excluded.
+///    Groups are sorted by their lowest user ID, shuffled, then stably sorted by size,
+///    largest first.
+/// 4. Each group joins the team with the fewest players, the lowest team number on ties.
+///
+/// With `keep_groups == false` every player is a group of one, so the teams differ
+/// by at most one player.
+pub fn split_teams(
+    captains: &[UserId],
+    groups: &[Vec<UserId>],
+    keep_groups: bool,
+    team_count: usize,
+    seed: u64,
+) -> Vec<Team> {
+    let mut rng = seeded_rng(seed);
+    let mut teams = vec![Team::default(); team_count];
+
+    let mut captains = sorted_unique(captains);
+    let captain_count = captains.len();
+    shuffle_prefix(&mut captains, captain_count, &mut rng);
+    let spare_captains = captains.split_off(team_count.min(captains.len()));
+    for (team, captain) in teams.iter_mut().zip(&captains) {
+        team.captain = Some(*captain);
+    }
+
+    // 先に現れたグループに所属させる。余った主将も一人のグループとして後で加える
+    let mut seen: HashSet<UserId> = captains.iter().chain(&spare_captains).copied().collect();
+    let mut units: Vec<Vec<UserId>> = groups
+        .iter()
+        .map(|group| group.iter().copied().filter(|id| seen.insert(*id)).collect::<Vec<UserId>>())
+        .chain(spare_captains.into_iter().map(|id| vec![id]))
+        .collect();
+
+    if !keep_groups {
+        units = units.into_iter().flatten().map(|id| vec![id]).collect();
+    }
+    for unit in units.iter_mut() {
+        unit.sort();
+    }
+    units.retain(|unit| !unit.is_empty());
+    units.sort_by_key(|unit| unit[0]);
+
+    let unit_count = units.len();
+    shuffle_prefix(&mut units, unit_count, &mut rng);
+    units.sort_by_key(|unit| std::cmp::Reverse(unit.len()));
+
+    for unit in units {
+        if let Some(team) = teams.iter_mut().min_by_key(|team| team.len()) {
+            team.members.extend(unit);
+        }
+    }
+
+    teams
+}
+
+fn sorted_unique(ids: &[UserId]) -> Vec<UserId> {
+    let mut sorted = ids.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[u64]) -> Vec<UserId> {
+        ids.iter().map(|&id| UserId::new(id)).collect()
+    }
+
+    fn all_players(teams: &[Team]) -> Vec<UserId> {
+        let mut players: Vec<UserId> = teams
+            .iter()
+            .flat_map(|team| team.captain.iter().chain(&team.members).copied())
+            .collect();
+        players.sort();
+        players
+    }
+
+    #[test]
+    fn test_teams_are_balanced_and_reproducible() {
+        let players = ids(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+
+        let teams = split_teams(&[], std::slice::from_ref(&players), false, 3, 42);
+
+        let mut sizes: Vec<usize> = teams.iter().map(Team::len).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 4, 4]);
+        assert_eq!(all_players(&teams), players);
+
+        // 入力順に依存しない
+        let mut reversed = players.clone();
+        reversed.reverse();
+        assert_eq!(split_teams(&[], &[reversed], false, 3, 42), teams);
+    }
+
+    #[test]
+    fn test_one_captain_per_team() {
+        let teams = split_teams(&ids(&[1, 2, 3]), &[ids(&[1, 4, 5, 6, 7])], false, 2, 7);
+
+        assert!(teams.iter().all(|team| team.captain.is_some()));
+        assert_eq!(teams.iter().map(Team::len).sum::<usize>(), 7);
+        assert_eq!(all_players(&teams), ids(&[1, 2, 3, 4, 5, 6, 7]));
+        // 余った主将は通常の参加者になる
+        assert!(teams.iter().any(|team| team.members.iter().any(|id| id.get() <= 3)));
+    }
+
+    #[test]
+    fn test_spare_captain_is_placed_once() {
+        for seed in 0..50 {
+            let teams = split_teams(&ids(&[1, 2, 3]), &[ids(&[1, 4, 5, 6, 7])], false, 2, seed);
+
+            assert_eq!(all_players(&teams), ids(&[1, 2, 3, 4, 5, 6, 7]), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_groups_stay_together() {
+        let groups = vec![ids(&[1, 2, 3]), ids(&[4, 5]), ids(&[3, 6])];
+
+        let teams = split_teams(&[], &groups, true, 2, 1);
+
+        let team_of = |id: u64| teams.iter().position(|team| team.members.contains(&UserId::new(id)));
+        assert_eq!(team_of(1), team_of(2));
+        assert_eq!(team_of(1), team_of(3));
+        assert_eq!(team_of(4), team_of(5));
+        assert_eq!(all_players(&teams), ids(&[1, 2, 3, 4, 5, 6]));
+    }
+}
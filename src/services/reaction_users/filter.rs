@@ -0,0 +1,276 @@
+use std::sync::LazyLock;
+use poise::serenity_prelude::{EmojiId, GuildId, ReactionType, RoleId, User, UserId};
+use regex::Regex;
+
+use crate::utils::parsers::parse_reactions;
+
+/// A custom emoji as Discord writes it, `<:name:id>` or `<a:name:id>`
+static CUSTOM_EMOJI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^<a?:\w+:(\d+)>$").unwrap());
+
+/// Which reactions of a message are taken into account.
+///
+/// An empty `include` list means every reaction is included.
+/// `exclude` always wins over `include`.
+#[derive(Debug, Clone, Default)]
+pub struct ReactionFilter {
+    pub include: Vec<ReactionMatcher>,
+    pub exclude: Vec<ReactionMatcher>,
+}
+
+/// A single emoji given by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReactionMatcher {
+    /// A unicode emoji, fully qualified when it is known to the emoji table
+    Unicode(String),
+    /// A custom emoji given as `<:name:id>`, `<a:name:id>` or its ID
+    Custom(EmojiId),
+    /// A custom emoji given by `:name:` or `name`
+    Name(String),
+}
+
+/// Which reactors are kept, based on their roles in the server.
+#[derive(Debug, Clone, Default)]
+pub struct RoleFilter {
+    /// Keep only members with this role
+    pub include: Option<RoleId>,
+    /// Drop members with this role
+    pub exclude: Option<RoleId>,
+}
+
+/// Which reactors are kept, based on who they are.
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    /// Drop bot accounts
+    pub exclude_bots: bool,
+    /// Drop this user, typically the one who invoked the command
+    pub exclude_user: Option<UserId>,
+}
+
+impl UserFilter {
+    /// Whether `user` passes the filter
+    pub fn is_match(&self, user: &User) -> bool {
+        !(self.exclude_bots && user.bot) && self.exclude_user != Some(user.id)
+    }
+}
+
+impl RoleFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_none() && self.exclude.is_none()
+    }
+
+    /// Whether a member with `roles` passes the filter.
+    ///
+    /// The `@everyone` role shares its ID with the guild and is held by every member,
+    /// even though it never appears in `roles`.
+    pub fn is_match(&self, guild_id: GuildId, roles: &[RoleId]) -> bool {
+        let has_role = |role_id: RoleId| role_id.get() == guild_id.get() || roles.contains(&role_id);
+
+        self.include.is_none_or(has_role) && !self.exclude.is_some_and(has_role)
+    }
+}
+
+impl ReactionFilter {
+    /// Parses space/comma-separated emoji lists.
+    ///
+    /// # Parameters
+    /// - `include`: Emojis to keep, or `None` to keep every reaction.
+    /// - `exclude`: Emojis to drop.
+    ///
+    /// # Example
+    /// ```
+    /// let filter = ReactionFilter::parse(Some("✅, :tada:"), Some("<:maybe:123>")).await;
+    /// ```
+    pub async fn parse(include: Option<&str>, exclude: Option<&str>) -> Self {
+        Self {
+            include: parse_matchers(include).await,
+            exclude: parse_matchers(exclude).await,
+        }
+    }
+
+    /// Whether every reaction passes the filter
+    #[cfg(feature = "snapshots")]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether the reaction passes the include and exclude lists
+    pub fn is_match(&self, reaction_type: &ReactionType) -> bool {
+        let is_included = self.include.is_empty()
+            || self.include.iter().any(|matcher| matcher.is_match(reaction_type));
+        let is_excluded = self.exclude.iter().any(|matcher| matcher.is_match(reaction_type));
+
+        is_included && !is_excluded
+    }
+}
+
+impl ReactionMatcher {
+    /// Normalizes one emoji token.
+    ///
+    /// Accepted forms:
+    /// - unicode emoji (`✅`), with or without variation selectors
+    /// - shortcodes (`:white_check_mark:`), resolved to the unicode emoji when known
+    /// - custom emoji (`<:name:id>`, `<a:name:id>`) or a bare emoji ID
+    /// - custom emoji names (`:name:` or `name`) that are not unicode shortcodes
+    ///
+    /// The explicit custom emoji form always wins, so a custom emoji whose name is also
+    /// a shortcode (e.g. `<:ok:123>`) is not mistaken for the unicode emoji.
+    pub fn parse(token: &str) -> Self {
+        if let Some(id) = CUSTOM_EMOJI
+            .captures(token)
+            .and_then(|cap| cap.get(1))
+            .and_then(|m| m.as_str().parse::<u64>().ok())
+        {
+            return ReactionMatcher::Custom(EmojiId::new(id));
+        }
+
+        if let Some(id) = token.parse::<u64>().ok().filter(|&id| id != 0) {
+            return ReactionMatcher::Custom(EmojiId::new(id));
+        }
+
+        let name = token.trim_matches(':');
+        if let Some(emoji) = emojis::get_by_shortcode(name) {
+            return ReactionMatcher::Unicode(emoji.as_str().to_string());
+        }
+
+        if let Some(emoji) = emojis::get(token) {
+            return ReactionMatcher::Unicode(emoji.as_str().to_string());
+        }
+
+        if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            ReactionMatcher::Name(name.to_string())
+        } else {
+            ReactionMatcher::Unicode(normalize_unicode(token))
+        }
+    }
+
+    pub fn is_match(&self, reaction_type: &ReactionType) -> bool {
+        match (self, reaction_type) {
+            (ReactionMatcher::Unicode(emoji), ReactionType::Unicode(reaction)) => {
+                normalize_unicode(emoji) == normalize_unicode(reaction)
+            }
+            (ReactionMatcher::Custom(id), ReactionType::Custom { id: reaction_id, .. }) => {
+                id == reaction_id
+            }
+            (ReactionMatcher::Name(name), ReactionType::Custom { name: Some(reaction_name), .. }) => {
+                name == reaction_name
+            }
+            _ => false,
+        }
+    }
+}
+
+async fn parse_matchers(input: Option<&str>) -> Vec<ReactionMatcher> {
+    match input {
+        Some(input) => parse_reactions(input)
+            .await
+            .iter()
+            .map(|token| ReactionMatcher::parse(token))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Brings a unicode emoji to a comparable form.
+///
+/// Discord does not always keep the variation selector (`U+FE0F`) that clients send,
+/// so both sides are compared without it.
+fn normalize_unicode(emoji: &str) -> String {
+    let qualified = emojis::get(emoji).map(|e| e.as_str()).unwrap_or(emoji);
+    qualified.chars().filter(|&c| c != '\u{FE0F}').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unicode(emoji: &str) -> ReactionType {
+        ReactionType::Unicode(emoji.to_string())
+    }
+
+    fn custom(id: u64, name: &str) -> ReactionType {
+        ReactionType::Custom {
+            animated: false,
+            id: EmojiId::new(id),
+            name: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_role_filter() {
+        let guild_id = GuildId::new(1);
+        let (member, guest) = (RoleId::new(10), RoleId::new(20));
+        let filter = RoleFilter { include: Some(member), exclude: Some(guest) };
+
+        assert!(filter.is_match(guild_id, &[member]));
+        assert!(!filter.is_match(guild_id, &[member, guest]));
+        assert!(!filter.is_match(guild_id, &[]));
+
+        let everyone = RoleFilter { include: Some(RoleId::new(1)), exclude: None };
+        assert!(everyone.is_match(guild_id, &[]));
+        assert!(RoleFilter::default().is_match(guild_id, &[guest]));
+    }
+
+    #[test]
+    fn test_user_filter() {
+        let mut bot = User::default();
+        bot.id = UserId::new(1);
+        bot.bot = true;
+        let mut invoker = User::default();
+        invoker.id = UserId::new(2);
+        let mut other = User::default();
+        other.id = UserId::new(3);
+
+        let filter = UserFilter { exclude_bots: true, exclude_user: Some(invoker.id) };
+        assert!(!filter.is_match(&bot));
+        assert!(!filter.is_match(&invoker));
+        assert!(filter.is_match(&other));
+
+        assert!(UserFilter::default().is_match(&bot));
+    }
+
+    #[test]
+    fn test_parse_matcher_forms() {
+        assert_eq!(ReactionMatcher::parse("✅"), ReactionMatcher::Unicode("✅".to_string()));
+        assert_eq!(
+            ReactionMatcher::parse(":white_check_mark:"),
+            ReactionMatcher::Unicode("✅".to_string())
+        );
+        assert_eq!(ReactionMatcher::parse("<:maybe:123>"), ReactionMatcher::Custom(EmojiId::new(123)));
+        assert_eq!(ReactionMatcher::parse("<a:party:456>"), ReactionMatcher::Custom(EmojiId::new(456)));
+        assert_eq!(ReactionMatcher::parse(":maybe:"), ReactionMatcher::Name("maybe".to_string()));
+    }
+
+    #[test]
+    fn test_parse_custom_emoji_named_like_shortcode() {
+        let matcher = ReactionMatcher::parse("<:ok:123>");
+
+        assert_eq!(matcher, ReactionMatcher::Custom(EmojiId::new(123)));
+        assert!(matcher.is_match(&custom(123, "ok")));
+        assert!(!matcher.is_match(&unicode("🆗")));
+        assert_eq!(ReactionMatcher::parse(":ok:"), ReactionMatcher::Unicode("🆗".to_string()));
+    }
+
+    #[test]
+    fn test_matcher_ignores_variation_selector() {
+        let matcher = ReactionMatcher::parse("❤️");
+        assert!(matcher.is_match(&unicode("❤")));
+        assert!(matcher.is_match(&unicode("❤️")));
+        assert!(ReactionMatcher::parse(":heart:").is_match(&unicode("❤")));
+    }
+
+    #[tokio::test]
+    async fn test_filter_include_and_exclude() {
+        let filter = ReactionFilter::parse(Some("✅, :tada: <:maybe:123>"), Some("👀")).await;
+
+        assert!(filter.is_match(&unicode("✅")));
+        assert!(filter.is_match(&unicode("🎉")));
+        assert!(filter.is_match(&custom(123, "maybe")));
+        assert!(!filter.is_match(&unicode("👀")));
+        assert!(!filter.is_match(&unicode("❌")));
+
+        let filter = ReactionFilter::parse(None, Some("👀 :maybe:")).await;
+        assert!(filter.is_match(&unicode("✅")));
+        assert!(!filter.is_match(&unicode("👀")));
+        assert!(!filter.is_match(&custom(123, "maybe")));
+    }
+}
@@ -0,0 +1,135 @@
+use poise::serenity_prelude::{CreateEmbed, Mentionable};
+
+use crate::services::reaction_users::renderers::ReactionReportRenderer;
+use crate::services::reaction_users::renderers::text::{get_reaction_empty_text, to_mentions};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+use crate::utils::embed_splitter::{group_pages, paginate_fields, EmbedField, EmbedPage};
+
+const EMBED_TITLE: &str = "Reactions";
+
+/// Renders the report as embeds with one field per reaction.
+///
+/// Mentions inside embeds do not notify anyone, so unlike the text output
+/// the users are listed only once. Fields, embeds and messages are split
+/// whenever one of Discord's embed limits would be exceeded.
+pub struct EmbedRenderer;
+
+impl ReactionReportRenderer for EmbedRenderer {
+    fn render(
+        &self,
+        report: &ReactionReport,
+        _parameter: &ReactionUsersParameter,
+    ) -> Result<ReactionUsersResponse, crate::Error> {
+        let embeds = group_pages(to_embed_pages(report))
+            .into_iter()
+            .map(|pages| {
+                pages
+                    .into_iter()
+                    .map(|page| to_create_embed(page, &report.message.url))
+                    .collect()
+            })
+            .collect();
+
+        let mut response = ReactionUsersResponse::text(String::new(), String::new());
+        response.embeds = embeds;
+        Ok(response)
+    }
+}
+
+/// Lays out the report: author and user count first, then one field per reaction,
+/// then the failures and warnings.
+fn to_embed_pages(report: &ReactionReport) -> Vec<EmbedPage> {
+    let mut fields = vec![
+        EmbedField {
+            name: "🧔 Author".to_string(),
+            value: report.author.mention().to_string(),
+            inline: true,
+        },
+        EmbedField {
+            name: "👥 Users".to_string(),
+            value: report.user_count().to_string(),
+            inline: true,
+        },
+    ];
+
+    // リアクションごとにフィールドを作る
+    for reaction in &report.reactions {
+        let name = format!("{} {}", reaction.emoji, reaction.users.len());
+        let value = if reaction.users.is_empty() {
+            "-".to_string()
+        } else {
+            to_mentions(&reaction.users)
+        };
+        fields.extend(EmbedField::split(&name, &value, false));
+    }
+
+    let notes: Vec<String> = report.failures
+        .iter()
+        .map(|failure| format!("Failed to fetch {}: {}", failure.emoji, failure.reason))
+        .chain(report.warnings.iter().cloned())
+        .collect();
+    if !notes.is_empty() {
+        fields.extend(EmbedField::split("⚠️ Warnings", &notes.join("\n"), false));
+    }
+
+    let description = if report.is_empty() { get_reaction_empty_text() } else { String::new() };
+    paginate_fields(EMBED_TITLE, &description, fields)
+}
+
+fn to_create_embed(page: EmbedPage, url: &str) -> CreateEmbed {
+    let embed = CreateEmbed::new().title(page.title).url(url);
+    let embed = if page.description.is_empty() { embed } else { embed.description(page.description) };
+
+    embed.fields(page.fields.into_iter().map(|field| (field.name, field.value, field.inline)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+    use crate::utils::embed_splitter::{EMBED_FIELD_COUNT_LIMIT, EMBED_TOTAL_LIMIT};
+
+    fn report(reactions: usize, users_per_reaction: u64) -> ReactionReport {
+        let fetched = (0..reactions)
+            .map(|i| {
+                let users = (0..users_per_reaction)
+                    .map(|j| user(100_000_000_000_000_000 + j, "u"))
+                    .collect();
+                fetched(&format!("e{}", i), users)
+            })
+            .collect();
+        ReactionReport::new(&message(user(1, "author")), String::new(), fetched, false)
+    }
+
+    #[test]
+    fn test_embed_fields_per_reaction() {
+        let pages = to_embed_pages(&report(2, 3));
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].title, EMBED_TITLE);
+        assert_eq!(pages[0].fields.len(), 4);
+        assert_eq!(pages[0].fields[0].value, "<@1>");
+        assert_eq!(pages[0].fields[1].value, "3");
+        assert_eq!(pages[0].fields[2].name, "e0 3");
+    }
+
+    #[test]
+    fn test_embed_spills_over_limits() {
+        let pages = to_embed_pages(&report(30, 60));
+
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(page.fields.len() <= EMBED_FIELD_COUNT_LIMIT);
+            assert!(page.len() <= EMBED_TOTAL_LIMIT);
+        }
+    }
+
+    #[test]
+    fn test_embed_empty_report() {
+        let pages = to_embed_pages(&report(0, 0));
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].description, get_reaction_empty_text());
+    }
+}
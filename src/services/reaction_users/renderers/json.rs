@@ -0,0 +1,197 @@
+use poise::serenity_prelude::{CreateAttachment, ReactionType, User};
+use serde::Serialize;
+
+use crate::services::reaction_users::renderers::ReactionReportRenderer;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_failures_text, get_reaction_users_header_text,
+};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+/// Renders the report as a machine-readable JSON attachment.
+///
+/// Snowflake IDs are written as strings so that they survive
+/// consumers that parse numbers as 64-bit floats.
+pub struct JsonRenderer;
+
+impl ReactionReportRenderer for JsonRenderer {
+    fn render(
+        &self,
+        report: &ReactionReport,
+        _parameter: &ReactionUsersParameter,
+    ) -> Result<ReactionUsersResponse, crate::Error> {
+        let document = serde_json::to_string_pretty(&JsonReport::from(report))?;
+        let filename = format!("reactions_{}.json", report.message.id);
+        let attachment = CreateAttachment::bytes(document, filename);
+
+        let content = format!(
+            "Reactions:\n  📎: {} ({} reactions, {} users)\n{}",
+            attachment.filename,
+            report.reactions.len(),
+            report.user_count(),
+            get_reaction_failures_text(report),
+        );
+
+        Ok(ReactionUsersResponse {
+            header: get_reaction_users_header_text(report),
+            content,
+            attachments: vec![attachment],
+            embeds: Vec::new(),
+            pages: Vec::new(),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    message: JsonMessage<'a>,
+    author: JsonUser<'a>,
+    reactions: Vec<JsonReaction>,
+    users: Vec<JsonUser<'a>>,
+    failures: Vec<JsonFailure<'a>>,
+    warnings: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonMessage<'a> {
+    id: String,
+    channel_id: String,
+    guild_id: Option<String>,
+    url: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonUser<'a> {
+    id: String,
+    username: &'a str,
+    display_name: &'a str,
+    is_bot: bool,
+}
+
+#[derive(Serialize)]
+struct JsonReaction {
+    emoji: JsonEmoji,
+    /// Count reported by Discord, including super reactions
+    count: u64,
+    user_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonEmoji {
+    Unicode {
+        name: String,
+    },
+    Custom {
+        id: String,
+        name: Option<String>,
+        animated: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct JsonFailure<'a> {
+    emoji: &'a str,
+    reason: &'a str,
+}
+
+impl<'a> From<&'a ReactionReport> for JsonReport<'a> {
+    fn from(report: &'a ReactionReport) -> Self {
+        Self {
+            message: JsonMessage {
+                id: report.message.id.to_string(),
+                channel_id: report.message.channel_id.to_string(),
+                guild_id: report.message.guild_id.map(|id| id.to_string()),
+                url: &report.message.url,
+            },
+            author: JsonUser::from(&report.author),
+            reactions: report.reactions
+                .iter()
+                .map(|reaction| JsonReaction {
+                    emoji: JsonEmoji::from(&reaction.reaction_type),
+                    count: reaction.count,
+                    user_ids: reaction.users.iter().map(|user| user.id.to_string()).collect(),
+                })
+                .collect(),
+            users: report.users.iter().map(JsonUser::from).collect(),
+            failures: report.failures
+                .iter()
+                .map(|failure| JsonFailure {
+                    emoji: &failure.emoji,
+                    reason: &failure.reason,
+                })
+                .collect(),
+            warnings: &report.warnings,
+        }
+    }
+}
+
+impl<'a> From<&'a User> for JsonUser<'a> {
+    fn from(user: &'a User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: &user.name,
+            display_name: user.display_name(),
+            is_bot: user.bot,
+        }
+    }
+}
+
+impl From<&ReactionType> for JsonEmoji {
+    fn from(reaction_type: &ReactionType) -> Self {
+        match reaction_type {
+            ReactionType::Custom { animated, id, name } => JsonEmoji::Custom {
+                id: id.to_string(),
+                name: name.clone(),
+                animated: *animated,
+            },
+            other => JsonEmoji::Unicode {
+                name: other.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poise::serenity_prelude::EmojiId;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+    use crate::services::reaction_users::report::FetchedReaction;
+
+    #[test]
+    fn test_json_report() {
+        let author = user(1, "author");
+        let fetched = vec![
+            fetched("👍", vec![user(2, "b")]),
+            FetchedReaction {
+                reaction_type: ReactionType::Custom {
+                    animated: true,
+                    id: EmojiId::new(42),
+                    name: Some("party".to_string()),
+                },
+                count: 1,
+                users: Ok(vec![user(1, "author")]),
+            },
+        ];
+        let report = ReactionReport::new(
+            &message(author),
+            "https://discord.com/channels/100/200/300".to_string(),
+            fetched,
+            false,
+        );
+
+        let value = serde_json::to_value(JsonReport::from(&report)).unwrap();
+
+        assert_eq!(value["message"]["id"], "300");
+        assert_eq!(value["message"]["guild_id"], "100");
+        assert_eq!(value["author"]["username"], "author");
+        assert_eq!(value["reactions"][0]["emoji"]["type"], "unicode");
+        assert_eq!(value["reactions"][0]["emoji"]["name"], "👍");
+        assert_eq!(value["reactions"][0]["user_ids"][0], "2");
+        assert_eq!(value["reactions"][1]["emoji"]["type"], "custom");
+        assert_eq!(value["reactions"][1]["emoji"]["id"], "42");
+        assert_eq!(value["reactions"][1]["emoji"]["animated"], true);
+        assert_eq!(value["users"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{OutputFormat, ReactionUsersParameter, ReactionUsersResponse};
+use crate::utils::delimited::Delimiter;
+
+pub mod embed;
+pub mod json;
+pub mod paginated;
+pub mod table;
+pub mod text;
+
+/// Turns a [`ReactionReport`] into the response sent back to the user.
+///
+/// Implement this trait to add an output format without touching the
+/// grouping, deduplication and counting done while building the report.
+pub trait ReactionReportRenderer {
+    fn render(
+        &self,
+        report: &ReactionReport,
+        parameter: &ReactionUsersParameter,
+    ) -> Result<ReactionUsersResponse, crate::Error>;
+}
+
+/// Returns the renderer for the requested output format.
+pub fn renderer_for(output_format: OutputFormat) -> Box<dyn ReactionReportRenderer + Send + Sync> {
+    match output_format {
+        OutputFormat::Text => Box::new(text::TextRenderer),
+        OutputFormat::Csv => Box::new(table::TableRenderer { delimiter: Delimiter::Comma }),
+        OutputFormat::Tsv => Box::new(table::TableRenderer { delimiter: Delimiter::Tab }),
+        OutputFormat::Json => Box::new(json::JsonRenderer),
+        OutputFormat::Embed => Box::new(embed::EmbedRenderer),
+        OutputFormat::Paginated => Box::new(paginated::PaginatedRenderer),
+    }
+}
+
+/// Renders the report as message contents that can be swapped in place.
+///
+/// A text report that fits in one message is returned as that single message;
+/// otherwise (or for [`OutputFormat::Paginated`]) the paginated pages are returned.
+pub fn render_pages(
+    report: &ReactionReport,
+    parameter: &ReactionUsersParameter,
+) -> Result<Vec<String>, crate::Error> {
+    if parameter.output_format == OutputFormat::Text {
+        let response = text::TextRenderer.render(report, parameter)?;
+        if response.fits_in_one_message() {
+            return Ok(vec![response.header + &response.content]);
+        }
+    }
+
+    Ok(paginated::PaginatedRenderer.render(report, parameter)?.pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+    use crate::services::reaction_users::types::tests::parameter;
+    use crate::utils::message_splitter::MESSAGE_CHARACTER_LIMIT;
+
+    fn report(users: u64) -> ReactionReport {
+        let users = (0..users).map(|id| user(100_000_000_000_000_000 + id, "u")).collect();
+        ReactionReport::new(&message(user(1, "author")), String::new(), vec![fetched("👍", users)], false)
+    }
+
+    #[test]
+    fn test_render_pages_keeps_short_text() {
+        let pages = render_pages(&report(3), &parameter()).unwrap();
+
+        assert_eq!(pages.len(), 1);
+        assert!(!pages[0].contains("Page 1/1"));
+    }
+
+    #[test]
+    fn test_render_pages_paginates_long_text() {
+        let pages = render_pages(&report(100), &parameter()).unwrap();
+
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(page.chars().count() <= MESSAGE_CHARACTER_LIMIT);
+        }
+    }
+}
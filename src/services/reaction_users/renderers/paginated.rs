@@ -0,0 +1,237 @@
+use poise::serenity_prelude::User;
+
+use crate::services::reaction_users::renderers::ReactionReportRenderer;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_empty_text, get_reaction_failures_text, get_reaction_users_header_text, to_mentions,
+};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+/// Number of users shown on one page.
+///
+/// Each user is rendered twice (mention and code block), so 30 users stay well
+/// below the message limit even with 19-digit IDs.
+pub const USERS_PER_PAGE: usize = 30;
+
+/// Longest failure and warning notes repeated on every page, in characters.
+///
+/// Notes beyond it are counted instead of shown, so that a page stays within the message limit.
+const MAX_NOTES_LENGTH: usize = 300;
+
+/// Renders the report as pages of at most [`USERS_PER_PAGE`] users.
+///
+/// The pages are computed once from the report, so browsing them does not fetch
+/// the reactions again. Every page repeats the header and ends with `Page i/n`.
+pub struct PaginatedRenderer;
+
+impl ReactionReportRenderer for PaginatedRenderer {
+    fn render(
+        &self,
+        report: &ReactionReport,
+        parameter: &ReactionUsersParameter,
+    ) -> Result<ReactionUsersResponse, crate::Error> {
+        let mut response = ReactionUsersResponse::text(String::new(), String::new());
+        response.pages = to_report_pages(report, parameter, USERS_PER_PAGE);
+        Ok(response)
+    }
+}
+
+/// One reaction's users on a page. `emoji` is `None` for the combined list.
+struct PageSection<'a> {
+    emoji: Option<&'a str>,
+    count: usize,
+    users: &'a [User],
+}
+
+/// Splits the report into pages. There is always at least one page.
+fn to_report_pages(
+    report: &ReactionReport,
+    parameter: &ReactionUsersParameter,
+    users_per_page: usize,
+) -> Vec<String> {
+    let header = get_reaction_users_header_text(report);
+    let failures = to_notes_text(&get_reaction_failures_text(report), MAX_NOTES_LENGTH);
+
+    // フィルタで全員が除外された場合や、全ての取得に失敗した場合も空のページを返す
+    if report.is_empty() || report.reactions.iter().all(|reaction| reaction.users.is_empty()) {
+        return vec![format!("{}{}{}", header, get_reaction_empty_text(), failures)];
+    }
+
+    let pages = if parameter.is_reaction_grouping {
+        to_grouped_sections(report, users_per_page)
+    } else {
+        report.users
+            .chunks(users_per_page.max(1))
+            .map(|users| vec![PageSection { emoji: None, count: report.user_count(), users }])
+            .collect()
+    };
+
+    let total = pages.len();
+    pages
+        .iter()
+        .enumerate()
+        .map(|(index, sections)| {
+            let body: String = sections
+                .iter()
+                .map(|section| to_section_text(section, parameter.is_show_count))
+                .collect();
+            format!("{}Reactions:\n{}{}\n\nPage {}/{}", header, body, failures, index + 1, total)
+        })
+        .collect()
+}
+
+/// Packs the users of each reaction into pages, splitting a reaction across pages
+/// when it has more users than fit on the current page.
+fn to_grouped_sections(report: &ReactionReport, users_per_page: usize) -> Vec<Vec<PageSection<'_>>> {
+    let users_per_page = users_per_page.max(1);
+    let mut pages: Vec<Vec<PageSection>> = vec![Vec::new()];
+    let mut room = users_per_page;
+
+    for reaction in &report.reactions {
+        let mut users = reaction.users.as_slice();
+        loop {
+            if room == 0 {
+                pages.push(Vec::new());
+                room = users_per_page;
+            }
+            let (head, tail) = users.split_at(users.len().min(room));
+            pages.last_mut().unwrap().push(PageSection {
+                emoji: Some(&reaction.emoji),
+                count: reaction.users.len(),
+                users: head,
+            });
+            room -= head.len();
+            users = tail;
+            if users.is_empty() {
+                break;
+            }
+        }
+    }
+
+    pages
+}
+
+/// Keeps the notes that fit in `limit` characters and counts the rest.
+///
+/// `notes` is [`get_reaction_failures_text`]: one note per line, each starting with a line break.
+fn to_notes_text(notes: &str, limit: usize) -> String {
+    let lines: Vec<&str> = notes.split('\n').filter(|line| !line.is_empty()).collect();
+    let mut text = String::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let line: String = if line.chars().count() > limit {
+            line.chars().take(limit.saturating_sub(1)).chain(std::iter::once('…')).collect()
+        } else {
+            line.to_string()
+        };
+        if text.chars().count() + line.chars().count() + 1 > limit {
+            text.push_str(&format!("\n⚠️ …and {} more notes", lines.len() - index));
+            break;
+        }
+        text.push('\n');
+        text.push_str(&line);
+    }
+    text
+}
+
+fn to_section_text(section: &PageSection, is_show_count: bool) -> String {
+    let mentions = to_mentions(section.users);
+    match section.emoji {
+        Some(emoji) if is_show_count => {
+            format!("  {}: {:>4}: {}```{}```\n", emoji, section.count, mentions, mentions)
+        }
+        Some(emoji) => format!("  {}: {}```{}```\n", emoji, mentions, mentions),
+        None => format!("{}\n```{}```\n", mentions, mentions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+    use crate::services::reaction_users::types::{self, OutputFormat};
+    use crate::utils::message_splitter::MESSAGE_CHARACTER_LIMIT;
+
+    fn parameter(is_reaction_grouping: bool) -> ReactionUsersParameter {
+        ReactionUsersParameter {
+            is_reaction_grouping,
+            is_show_count: true,
+            output_format: OutputFormat::Paginated,
+            ..types::tests::parameter()
+        }
+    }
+
+    fn report() -> ReactionReport {
+        let users = |from: u64, count: u64| {
+            (from..from + count).map(|id| user(100_000_000_000_000_000 + id, "u")).collect()
+        };
+        let fetched = vec![fetched("👍", users(0, 5)), fetched("🎉", users(5, 3))];
+        ReactionReport::new(&message(user(1, "author")), String::new(), fetched, false)
+    }
+
+    #[test]
+    fn test_combined_pages() {
+        let pages = to_report_pages(&report(), &parameter(false), 3);
+
+        assert_eq!(pages.len(), 3);
+        assert!(pages[0].ends_with("Page 1/3"));
+        assert!(pages[2].ends_with("Page 3/3"));
+        // 2 users twice, plus the author in the header
+        assert_eq!(pages[2].matches("<@").count(), 5);
+    }
+
+    #[test]
+    fn test_grouped_pages_split_reactions() {
+        let pages = to_report_pages(&report(), &parameter(true), 4);
+
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].contains("👍:    5:"));
+        assert!(!pages[0].contains("🎉"));
+        assert!(pages[1].contains("👍:    5:"));
+        assert!(pages[1].contains("🎉:    3:"));
+        assert_eq!(pages[1].matches("<@").count(), 9);
+    }
+
+    #[test]
+    fn test_fully_filtered_report_has_one_empty_page() {
+        let fetched = vec![fetched("👍", Vec::new()), fetched("🎉", Vec::new())];
+        let report = ReactionReport::new(&message(user(1, "author")), String::new(), fetched, false);
+
+        for is_reaction_grouping in [false, true] {
+            let pages = to_report_pages(&report, &parameter(is_reaction_grouping), USERS_PER_PAGE);
+            assert_eq!(pages.len(), 1);
+            assert!(pages[0].contains(&get_reaction_empty_text()));
+        }
+    }
+
+    #[test]
+    fn test_notes_are_bounded() {
+        let mut report = report();
+        report.warnings = (0..50).map(|index| format!("User {} could not be resolved as a member.", index)).collect();
+
+        let pages = to_report_pages(&report, &parameter(false), USERS_PER_PAGE);
+
+        assert!(pages[0].chars().count() <= MESSAGE_CHARACTER_LIMIT);
+        assert!(pages[0].contains("more notes"));
+        assert!(pages[0].contains("User 0 could not"));
+    }
+
+    #[test]
+    fn test_default_page_fits_in_a_message() {
+        let users = (0..100).map(|id| user(100_000_000_000_000_000 + id, "u")).collect();
+        let report = ReactionReport::new(
+            &message(user(1, "author")),
+            "https://discord.com/channels/100/200/300".to_string(),
+            vec![fetched("👍", users)],
+            false,
+        );
+
+        for is_reaction_grouping in [false, true] {
+            let pages = to_report_pages(&report, &parameter(is_reaction_grouping), USERS_PER_PAGE);
+            assert_eq!(pages.len(), 4);
+            for page in &pages {
+                assert!(page.chars().count() <= MESSAGE_CHARACTER_LIMIT);
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use poise::serenity_prelude::{CreateAttachment, User};
+
+use crate::services::reaction_users::renderers::ReactionReportRenderer;
+use crate::services::reaction_users::renderers::text::{
+    get_reaction_empty_text, get_reaction_failures_text, get_reaction_users_header_text,
+};
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+use crate::utils::delimited::{to_delimited, Delimiter};
+
+/// Column names of the attached reaction table.
+const REACTION_TABLE_HEADER: [&str; 6] = [
+    "emoji", "user_id", "username", "display_name", "is_bot", "is_author",
+];
+
+/// Renders the report as a CSV/TSV attachment with one row per (emoji, user) pair.
+pub struct TableRenderer {
+    pub delimiter: Delimiter,
+}
+
+impl ReactionReportRenderer for TableRenderer {
+    fn render(
+        &self,
+        report: &ReactionReport,
+        parameter: &ReactionUsersParameter,
+    ) -> Result<ReactionUsersResponse, crate::Error> {
+        let header_text = get_reaction_users_header_text(report);
+
+        if report.is_empty() {
+            return Ok(ReactionUsersResponse::text(header_text, get_reaction_empty_text()));
+        }
+
+        let rows = to_reaction_table_rows(report, parameter);
+        let row_count = rows.len();
+        let document = to_delimited(&REACTION_TABLE_HEADER, rows, self.delimiter);
+        let filename = format!("reactions_{}.{}", report.message.id, self.delimiter.extension());
+        let attachment = CreateAttachment::bytes(document, filename);
+
+        Ok(ReactionUsersResponse {
+            header: header_text,
+            content: get_reaction_table_text(&attachment, row_count)
+                + &get_reaction_failures_text(report),
+            attachments: vec![attachment],
+            embeds: Vec::new(),
+            pages: Vec::new(),
+        })
+    }
+}
+
+/// Builds one row per (emoji, user) pair.
+///
+/// When `is_author_include` is set and the author did not react, the author is added
+/// as a row with an empty emoji column, mirroring the text output. Like there, an author
+/// dropped by the user filter (a bot, or the invoker with exclude_self) is not added.
+fn to_reaction_table_rows(
+    report: &ReactionReport,
+    parameter: &ReactionUsersParameter,
+) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = report.reactions
+        .iter()
+        .flat_map(|reaction| {
+            reaction.users.iter().map(|user| to_reaction_table_row(&reaction.emoji, user, report))
+        })
+        .collect();
+
+    // メッセージの発言者も含める。除外対象の発言者は含めない
+    if parameter.is_author_include
+        && parameter.user_filter.is_match(&report.author)
+        && !report.is_author_reacted()
+    {
+        rows.insert(0, to_reaction_table_row("", &report.author, report));
+    }
+
+    rows
+}
+
+fn to_reaction_table_row(emoji: &str, user: &User, report: &ReactionReport) -> Vec<String> {
+    vec![
+        emoji.to_string(),
+        user.id.to_string(),
+        user.name.clone(),
+        user.display_name().to_string(),
+        user.bot.to_string(),
+        (user.id == report.author.id).to_string(),
+    ]
+}
+
+fn get_reaction_table_text(attachment: &CreateAttachment, rows: usize) -> String {
+    format!("Reactions:\n  📎: {} ({} rows)\n", attachment.filename, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_users::filter::UserFilter;
+    use crate::services::reaction_users::report::tests::{fetched, message, user};
+    use crate::services::reaction_users::types::tests::parameter;
+
+    #[test]
+    fn test_author_row_follows_user_filter() {
+        let author = user(1, "author");
+        let reactions = vec![fetched("✅", vec![user(10, "a")])];
+        let report = ReactionReport::new(&message(author.clone()), String::new(), reactions, false);
+        let mut parameter = parameter();
+        parameter.is_author_include = true;
+
+        assert_eq!(to_reaction_table_rows(&report, &parameter).len(), 2);
+
+        parameter.user_filter = UserFilter { exclude_bots: false, exclude_user: Some(author.id) };
+        let rows = to_reaction_table_rows(&report, &parameter);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][1], "10");
+    }
+}
@@ -0,0 +1,112 @@
+use poise::serenity_prelude::{Mentionable, User};
+
+use crate::services::reaction_users::renderers::ReactionReportRenderer;
+use crate::services::reaction_users::report::ReactionReport;
+use crate::services::reaction_users::types::{ReactionUsersParameter, ReactionUsersResponse};
+
+/// Renders the report as mentions in the message body.
+///
+/// Each mention list is followed by the same list in a code block,
+/// so it can be copied without pinging anyone.
+pub struct TextRenderer;
+
+impl ReactionReportRenderer for TextRenderer {
+    fn render(
+        &self,
+        report: &ReactionReport,
+        parameter: &ReactionUsersParameter,
+    ) -> Result<ReactionUsersResponse, crate::Error> {
+        let header_text = get_reaction_users_header_text(report);
+
+        let text = if report.is_empty() {
+            // メッセージにリアクションがない場合
+            get_reaction_empty_text()
+        } else if parameter.is_reaction_grouping {
+            // リアクションごとにユーザーを取得
+            get_reaction_grouping_text(report, parameter)
+        } else {
+            // 全てのリアクションを合算してユーザーを取得
+            get_reaction_users_text(report)
+        };
+
+        Ok(ReactionUsersResponse::text(
+            header_text,
+            text + &get_reaction_failures_text(report),
+        ))
+    }
+}
+
+/// Header (message link and author) shared by every output format.
+pub fn get_reaction_users_header_text(report: &ReactionReport) -> String {
+
+    let author_mention = report.author.mention();
+
+    // ヘッダ情報（メッセージリンク、発言者）
+    format!(r###"
+Information
+  📝: {}
+  🧔: {}
+
+"###, report.message.url, author_mention)
+}
+
+pub fn get_reaction_empty_text() -> String {
+    "No one reacted.".to_string()
+}
+
+/// Lists the deduplicated users of all reactions.
+///
+/// The mentions are rendered once as mentions and once inside a code block,
+/// separated by a single space.
+pub fn get_reaction_users_text(report: &ReactionReport) -> String {
+
+    let users = to_mentions(&report.users);
+
+    format!(r###"
+Reactions:
+{}
+```{}```
+"###, users, users)
+}
+
+/// Lists the users of each reaction separately.
+pub fn get_reaction_grouping_text(
+    report: &ReactionReport,
+    parameter: &ReactionUsersParameter
+) -> String {
+
+    let results = report.reactions
+        .iter()
+        .map(|reaction| {
+            let count = reaction.users.len();
+            let user_mentions = to_mentions(&reaction.users);
+
+            if parameter.is_show_count {
+                format!("  {}: {:>4}: {}```{}```", reaction.emoji, count, user_mentions, user_mentions)
+            } else {
+                format!("  {}: {}```{}```", reaction.emoji, user_mentions, user_mentions)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!("Reactions:\n{}", results)
+}
+
+/// Notes the reactions whose users could not be fetched, followed by the report warnings.
+pub fn get_reaction_failures_text(report: &ReactionReport) -> String {
+    report.failures
+        .iter()
+        .map(|failure| format!("\n⚠️ Failed to fetch {}: {}", failure.emoji, failure.reason))
+        .chain(report.warnings.iter().map(|warning| format!("\n⚠️ {}", warning)))
+        .collect()
+}
+
+/// Joins the mentions of `users` with single spaces.
+pub fn to_mentions(users: &[User]) -> String {
+    users
+        .iter()
+        .map(|x| x.mention().to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use poise::serenity_prelude::{ChannelId, GuildId, Message, MessageId, ReactionType, User, UserId};
+
+use crate::services::reaction_users::types::UserSort;
+
+/// Reaction data of a single message, independent of how it is rendered.
+#[derive(Debug, Clone)]
+pub struct ReactionReport {
+    /// The message the reactions belong to
+    pub message: MessageMeta,
+    /// The author of the message
+    pub author: User,
+    /// Users for each reaction, in the order Discord reports the reactions
+    pub reactions: Vec<ReactionUsers>,
+    /// Deduplicated union of all reacting users, in first-seen order.
+    /// The author comes first when `is_author_include` was requested,
+    /// unless the users are sorted with [`ReactionReport::sort_users`].
+    pub users: Vec<User>,
+    /// Reactions whose users could not be fetched
+    pub failures: Vec<ReactionFetchFailure>,
+    /// Notes about users left out of (or kept in) the report, e.g. unresolved members
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageMeta {
+    pub id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReactionUsers {
+    pub reaction_type: ReactionType,
+    /// `reaction_type.to_string()`, the key used by `to_reaction_list`
+    pub emoji: String,
+    /// Count reported by Discord (includes super reactions)
+    pub count: u64,
+    pub users: Vec<User>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReactionFetchFailure {
+    pub emoji: String,
+    pub reason: String,
+}
+
+/// Result of fetching the users of one reaction.
+#[derive(Debug)]
+pub struct FetchedReaction {
+    pub reaction_type: ReactionType,
+    pub count: u64,
+    pub users: Result<Vec<User>, String>,
+}
+
+impl ReactionReport {
+    /// Assembles a report from the fetched reactions of `message`.
+    ///
+    /// # Parameters
+    /// - `message`: The message the reactions were fetched from.
+    /// - `url`: The link to the message (see `make_message_url`).
+    /// - `fetched`: The fetch result for each reaction, in Discord's order.
+    /// - `is_author_include`: Whether the author is prepended to the deduplicated user list.
+    pub fn new(
+        message: &Message,
+        url: String,
+        fetched: Vec<FetchedReaction>,
+        is_author_include: bool,
+    ) -> Self {
+        let mut reactions = Vec::new();
+        let mut failures = Vec::new();
+
+        for reaction in fetched {
+            let emoji = reaction.reaction_type.to_string();
+            match reaction.users {
+                Ok(users) => reactions.push(ReactionUsers {
+                    reaction_type: reaction.reaction_type,
+                    emoji,
+                    count: reaction.count,
+                    users,
+                }),
+                Err(reason) => failures.push(ReactionFetchFailure { emoji, reason }),
+            }
+        }
+
+        let mut report = Self {
+            message: MessageMeta {
+                id: message.id,
+                channel_id: message.channel_id,
+                guild_id: message.guild_id,
+                url,
+            },
+            author: message.author.clone(),
+            reactions,
+            users: Vec::new(),
+            failures,
+            warnings: Vec::new(),
+        };
+        report.rebuild_users(is_author_include);
+        report
+    }
+
+    /// Recomputes the deduplicated user list from the reactions, in first-seen order.
+    ///
+    /// Call [`ReactionReport::sort_users`] again afterwards if the users were sorted.
+    pub fn rebuild_users(&mut self, is_author_include: bool) {
+        // メッセージの発言者も含める
+        let author = is_author_include.then(|| self.author.clone());
+        self.users = dedup_users(
+            author
+                .into_iter()
+                .chain(self.reactions.iter().flat_map(|reaction| reaction.users.iter().cloned())),
+        );
+    }
+
+    /// Whether the message has no reactions at all
+    pub fn is_empty(&self) -> bool {
+        self.reactions.is_empty() && self.failures.is_empty()
+    }
+
+    /// Number of distinct users in the deduplicated list
+    pub fn user_count(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Sorts the deduplicated list and the users of each reaction.
+    ///
+    /// Name comparisons ignore case and fall back to the user ID, so the order is stable
+    /// between runs. [`UserSort::ReactionOrder`] keeps the order returned by Discord.
+    pub fn sort_users(&mut self, sort: UserSort) {
+        if sort == UserSort::ReactionOrder {
+            return;
+        }
+
+        let key = |user: &User| -> (String, UserId) {
+            let name = match sort {
+                UserSort::Username => user.name.to_lowercase(),
+                UserSort::DisplayName => user.display_name().to_lowercase(),
+                UserSort::ReactionOrder | UserSort::UserId => String::new(),
+            };
+            (name, user.id)
+        };
+
+        self.users.sort_by_cached_key(key);
+        for reaction in &mut self.reactions {
+            reaction.users.sort_by_cached_key(key);
+        }
+    }
+
+    /// Whether the author reacted with at least one reaction
+    pub fn is_author_reacted(&self) -> bool {
+        self.reactions
+            .iter()
+            .flat_map(|reaction| reaction.users.iter())
+            .any(|user| user.id == self.author.id)
+    }
+}
+
+/// Removes duplicated users while keeping the order of first appearance.
+pub fn dedup_users(users: impl IntoIterator<Item = User>) -> Vec<User> {
+    let mut seen: HashSet<UserId> = HashSet::new();
+    users
+        .into_iter()
+        .filter(|user| seen.insert(user.id))
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn user(id: u64, name: &str) -> User {
+        let mut user = User::default();
+        user.id = UserId::new(id);
+        user.name = name.to_string();
+        user
+    }
+
+    pub(crate) fn message(author: User) -> Message {
+        let mut message = Message::default();
+        message.id = MessageId::new(300);
+        message.channel_id = ChannelId::new(200);
+        message.guild_id = Some(GuildId::new(100));
+        message.author = author;
+        message
+    }
+
+    pub(crate) fn fetched(emoji: &str, users: Vec<User>) -> FetchedReaction {
+        FetchedReaction {
+            reaction_type: ReactionType::Unicode(emoji.to_string()),
+            count: users.len() as u64,
+            users: Ok(users),
+        }
+    }
+
+    #[test]
+    fn test_new_dedups_users_in_first_seen_order() {
+        let (a, b, c) = (user(1, "a"), user(2, "b"), user(3, "c"));
+        let fetched = vec![
+            fetched("👍", vec![b.clone(), a.clone()]),
+            fetched("❤️", vec![c.clone(), b.clone()]),
+        ];
+
+        let report = ReactionReport::new(&message(a.clone()), String::new(), fetched, false);
+
+        let ids: Vec<u64> = report.users.iter().map(|u| u.id.get()).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert_eq!(report.user_count(), 3);
+        assert!(report.is_author_reacted());
+        assert_eq!(report.reactions[0].emoji, "👍");
+        assert_eq!(report.reactions[1].emoji, "❤️");
+    }
+
+    #[test]
+    fn test_new_includes_author_first() {
+        let (author, b) = (user(1, "author"), user(2, "b"));
+        let fetched = vec![fetched("👍", vec![b.clone(), author.clone()])];
+
+        let report = ReactionReport::new(&message(author), String::new(), fetched, true);
+
+        let ids: Vec<u64> = report.users.iter().map(|u| u.id.get()).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_rebuild_users_toggles_author() {
+        let (author, b) = (user(1, "author"), user(2, "b"));
+        let fetched = vec![fetched("👍", vec![b.clone()])];
+        let mut report = ReactionReport::new(&message(author), String::new(), fetched, false);
+
+        report.rebuild_users(true);
+        let ids: Vec<u64> = report.users.iter().map(|u| u.id.get()).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        report.rebuild_users(false);
+        let ids: Vec<u64> = report.users.iter().map(|u| u.id.get()).collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn test_sort_users() {
+        let mut zed = user(1, "zed");
+        zed.global_name = Some("Alice".to_string());
+        let fetched = vec![
+            fetched("👍", vec![user(3, "Bob"), zed.clone()]),
+            fetched("❤️", vec![user(2, "carol"), user(3, "Bob")]),
+        ];
+        let mut report = ReactionReport::new(&message(user(9, "author")), String::new(), fetched, false);
+        let ids = |users: &[User]| users.iter().map(|u| u.id.get()).collect::<Vec<u64>>();
+
+        report.sort_users(UserSort::ReactionOrder);
+        assert_eq!(ids(&report.users), vec![3, 1, 2]);
+
+        report.sort_users(UserSort::Username);
+        assert_eq!(ids(&report.users), vec![3, 2, 1]);
+        assert_eq!(ids(&report.reactions[1].users), vec![3, 2]);
+
+        report.sort_users(UserSort::DisplayName);
+        assert_eq!(ids(&report.users), vec![1, 3, 2]);
+
+        report.sort_users(UserSort::UserId);
+        assert_eq!(ids(&report.users), vec![1, 2, 3]);
+        assert_eq!(ids(&report.reactions[0].users), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_new_collects_failures() {
+        let fetched = vec![
+            fetched("👍", vec![user(2, "b")]),
+            FetchedReaction {
+                reaction_type: ReactionType::Unicode("❤️".to_string()),
+                count: 3,
+                users: Err("Missing Access".to_string()),
+            },
+        ];
+
+        let report = ReactionReport::new(&message(user(1, "a")), String::new(), fetched, false);
+
+        assert_eq!(report.reactions.len(), 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].emoji, "❤️");
+        assert!(!report.is_empty());
+        assert!(!report.is_author_reacted());
+    }
+}
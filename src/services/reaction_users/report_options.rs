@@ -0,0 +1,194 @@
+use anyhow::{anyhow, Result};
+use poise::ChoiceParameter;
+use poise::serenity_prelude::{GuildId, RoleId};
+use regex::Regex;
+
+use crate::services::reaction_users::filter::RoleFilter;
+use crate::services::reaction_users::types::{OutputFormat, UserSort};
+
+/// On/off options typed as keywords, e.g. `grouping, count, author`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportFlags {
+    pub is_reaction_grouping: bool,
+    pub is_show_count: bool,
+    pub is_author_include: bool,
+    pub exclude_bots: bool,
+    pub exclude_self: bool,
+}
+
+/// Keywords accepted by [`parse_report_flags`].
+pub const REPORT_FLAG_KEYWORDS: [&str; 5] = ["grouping", "count", "author", "no-bots", "no-self"];
+
+/// Parses comma/space-separated option keywords (see [`REPORT_FLAG_KEYWORDS`]).
+///
+/// # Errors
+/// Returns an error naming the first unknown keyword.
+pub fn parse_report_flags(input: &str) -> Result<ReportFlags> {
+    let mut flags = ReportFlags::default();
+
+    for keyword in split_list(input, &[',', ' ']) {
+        let flag = match keyword.to_ascii_lowercase().as_str() {
+            "grouping" => &mut flags.is_reaction_grouping,
+            "count" => &mut flags.is_show_count,
+            "author" => &mut flags.is_author_include,
+            "no-bots" => &mut flags.exclude_bots,
+            "no-self" => &mut flags.exclude_self,
+            _ => {
+                return Err(anyhow!(
+                    "Unknown option '{}'. Use {}.",
+                    keyword,
+                    REPORT_FLAG_KEYWORDS.join(", "),
+                ))
+            }
+        };
+        *flag = true;
+    }
+
+    Ok(flags)
+}
+
+/// Parses a comma-separated output format and user order, in any order, e.g. `csv, username`.
+///
+/// Names are the choice names of the slash command, compared case-insensitively.
+/// Missing values fall back to their defaults.
+///
+/// # Errors
+/// Returns an error for an unknown name or when a format or order is given twice.
+pub fn parse_output_options(input: &str) -> Result<(OutputFormat, UserSort)> {
+    let mut output_format = None;
+    let mut sort = None;
+
+    for name in split_list(input, &[',']) {
+        if let Some(value) = OutputFormat::from_name(name) {
+            if output_format.replace(value).is_some() {
+                return Err(anyhow!("Only one output format can be given."));
+            }
+        } else if let Some(value) = UserSort::from_name(name) {
+            if sort.replace(value).is_some() {
+                return Err(anyhow!("Only one sort order can be given."));
+            }
+        } else {
+            return Err(anyhow!(
+                "Unknown output format or sort order '{}'. Use {} or {}.",
+                name,
+                choice_names::<OutputFormat>(),
+                choice_names::<UserSort>(),
+            ));
+        }
+    }
+
+    Ok((output_format.unwrap_or_default(), sort.unwrap_or_default()))
+}
+
+/// Resolves a comma-separated role list, e.g. `staff, -guest`, to a [`RoleFilter`].
+///
+/// Roles prefixed with `-` are excluded, the others included. Each role is given as
+/// a mention, an ID or a name (case-insensitive, optionally prefixed with `@`).
+///
+/// # Errors
+/// Returns an error for an unknown role, or when more than one role is included or excluded.
+pub async fn resolve_role_filter(
+    ctx: crate::Context<'_>,
+    guild_id: GuildId,
+    input: &str,
+) -> Result<RoleFilter, crate::Error> {
+    let tokens = split_list(input, &[',']);
+    if tokens.is_empty() {
+        return Ok(RoleFilter::default());
+    }
+
+    let roles = guild_id.roles(ctx).await?;
+    let names: Vec<(RoleId, &str)> = roles.iter().map(|(id, role)| (*id, role.name.as_str())).collect();
+
+    let mut filter = RoleFilter::default();
+    for token in tokens {
+        let (slot, name) = match token.strip_prefix('-') {
+            Some(name) => (&mut filter.exclude, name.trim()),
+            None => (&mut filter.include, token),
+        };
+        let role_id = find_role(name, &names).ok_or_else(|| format!("Unknown role '{}'.", name))?;
+        if slot.replace(role_id).is_some() {
+            return Err("Only one role can be included and one excluded.".into());
+        }
+    }
+
+    Ok(filter)
+}
+
+/// Finds a role by mention, ID or name among `roles`.
+fn find_role(token: &str, roles: &[(RoleId, &str)]) -> Option<RoleId> {
+    let mention = Regex::new(r"^<@&(\d+)>$").unwrap();
+    let id = mention
+        .captures(token)
+        .and_then(|cap| cap.get(1))
+        .map(|m| m.as_str())
+        .unwrap_or(token)
+        .parse::<u64>()
+        .ok();
+
+    let name = token.strip_prefix('@').unwrap_or(token);
+    roles
+        .iter()
+        .find(|(role_id, role_name)| Some(role_id.get()) == id || role_name.eq_ignore_ascii_case(name))
+        .map(|(role_id, _)| *role_id)
+}
+
+fn split_list<'a>(input: &'a str, separators: &[char]) -> Vec<&'a str> {
+    input
+        .split(separators)
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn choice_names<T: ChoiceParameter>() -> String {
+    T::list()
+        .iter()
+        .map(|choice| format!("'{}'", choice.name))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_report_flags() {
+        let flags = parse_report_flags("Grouping, count no-bots").unwrap();
+
+        assert!(flags.is_reaction_grouping);
+        assert!(flags.is_show_count);
+        assert!(flags.exclude_bots);
+        assert!(!flags.is_author_include);
+        assert!(!flags.exclude_self);
+        assert_eq!(parse_report_flags("").unwrap(), ReportFlags::default());
+        assert!(parse_report_flags("grouping, everything").is_err());
+    }
+
+    #[test]
+    fn test_parse_output_options() {
+        assert_eq!(
+            parse_output_options("display name, CSV").unwrap(),
+            (OutputFormat::Csv, UserSort::DisplayName)
+        );
+        assert_eq!(
+            parse_output_options("pages").unwrap(),
+            (OutputFormat::Paginated, UserSort::ReactionOrder)
+        );
+        assert_eq!(parse_output_options("").unwrap(), (OutputFormat::Text, UserSort::ReactionOrder));
+        assert!(parse_output_options("csv, json").is_err());
+        assert!(parse_output_options("xml").is_err());
+    }
+
+    #[test]
+    fn test_find_role() {
+        let roles = [(RoleId::new(10), "Staff"), (RoleId::new(20), "guest")];
+
+        assert_eq!(find_role("staff", &roles), Some(RoleId::new(10)));
+        assert_eq!(find_role("@Guest", &roles), Some(RoleId::new(20)));
+        assert_eq!(find_role("<@&20>", &roles), Some(RoleId::new(20)));
+        assert_eq!(find_role("10", &roles), Some(RoleId::new(10)));
+        assert_eq!(find_role("admin", &roles), None);
+    }
+}
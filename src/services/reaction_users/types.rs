@@ -1,4 +1,60 @@
-use poise::serenity_prelude::Message;
+use poise::CreateReply;
+use poise::serenity_prelude::{CreateAttachment, CreateEmbed, Message};
+
+use crate::services::reaction_users::filter::{ReactionFilter, ReactionMatcher, RoleFilter, UserFilter};
+use crate::utils::message_splitter::{split_message, MESSAGE_CHARACTER_LIMIT};
+
+/// Maximum number of messages a report is split into before falling back to a file.
+pub const MAX_SPLIT_MESSAGES: usize = 5;
+
+/// File name used when the report is too long to be sent as messages.
+const FALLBACK_FILENAME: &str = "reactions.txt";
+
+/// Output format of the reaction report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum OutputFormat {
+    /// Mentions pasted into the message body
+    #[default]
+    #[name = "text"]
+    #[name_localized("ja", "テキスト")]
+    Text,
+    /// Comma-separated values file attachment
+    #[name = "csv"]
+    Csv,
+    /// Tab-separated values file attachment
+    #[name = "tsv"]
+    Tsv,
+    /// Machine-readable JSON document attachment
+    #[name = "json"]
+    Json,
+    /// Embeds with one field per reaction
+    #[name = "embed"]
+    #[name_localized("ja", "埋め込み")]
+    Embed,
+    /// Text split into pages browsed with buttons
+    #[name = "pages"]
+    #[name_localized("ja", "ページ送り")]
+    Paginated,
+}
+
+/// Order of the users within each list of the report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum UserSort {
+    /// Order in which Discord returns the reactors, reactions in Discord's order
+    #[default]
+    #[name = "reaction order"]
+    #[name_localized("ja", "リアクション順")]
+    ReactionOrder,
+    #[name = "username"]
+    #[name_localized("ja", "ユーザー名")]
+    Username,
+    #[name = "display name"]
+    #[name_localized("ja", "表示名")]
+    DisplayName,
+    #[name = "user ID"]
+    #[name_localized("ja", "ユーザーID")]
+    UserId,
+}
 
 #[derive(Debug)]
 pub struct ReactionUsersParameter {
@@ -6,9 +62,201 @@ pub struct ReactionUsersParameter {
     pub is_reaction_grouping: bool,
     pub is_author_include: bool,
     pub is_show_count: bool,
+    pub output_format: OutputFormat,
+    pub reaction_filter: ReactionFilter,
+    pub role_filter: RoleFilter,
+    pub user_filter: UserFilter,
+    pub sort: UserSort,
+}
+
+impl ReactionUsersParameter {
+    /// A text report of the reactions with `emojis`, or of every reaction when there are none.
+    ///
+    /// Every other option is off; commands that build on the report set the ones they need.
+    pub fn for_emojis<'a>(message: &Message, emojis: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            message: message.clone(),
+            is_reaction_grouping: false,
+            is_author_include: false,
+            is_show_count: false,
+            output_format: OutputFormat::Text,
+            reaction_filter: ReactionFilter {
+                include: emojis.into_iter().map(ReactionMatcher::parse).collect(),
+                exclude: Vec::new(),
+            },
+            role_filter: RoleFilter::default(),
+            user_filter: UserFilter::default(),
+            sort: UserSort::default(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ReactionUsersResponse {
+    /// Information header (message link, author), kept on the first message
+    pub header: String,
     pub content: String,
-}
\ No newline at end of file
+    pub attachments: Vec<CreateAttachment>,
+    /// Embeds grouped by the message they are sent in
+    pub embeds: Vec<Vec<CreateEmbed>>,
+    /// Complete message contents, browsed one at a time with buttons
+    pub pages: Vec<String>,
+}
+
+impl ReactionUsersResponse {
+    /// Text-only response
+    pub fn text(header: String, content: String) -> Self {
+        Self {
+            header,
+            content,
+            attachments: Vec::new(),
+            embeds: Vec::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// Whether the header and text fit in one message, so that no split or file is needed
+    pub fn fits_in_one_message(&self) -> bool {
+        self.header.chars().count() + self.content.chars().count() <= MESSAGE_CHARACTER_LIMIT
+    }
+
+    /// Builds the replies sent back to the interaction.
+    ///
+    /// Output longer than Discord's message limit is split across up to
+    /// [`MAX_SPLIT_MESSAGES`] messages. Beyond that, the header is sent alone and
+    /// the rest is attached as `reactions.txt`. Attachments go on the first message.
+    ///
+    /// Embed responses are sent as one message per embed group instead,
+    /// with the text (if any) on the first message.
+    ///
+    /// Paginated responses are meant to be browsed with buttons
+    /// (see `pagination_component`); here every page becomes its own message.
+    pub fn into_replies(self) -> Vec<CreateReply> {
+        if !self.embeds.is_empty() {
+            return self.into_embed_replies();
+        }
+        if !self.pages.is_empty() {
+            return self.pages.into_iter().map(|page| CreateReply::default().content(page)).collect();
+        }
+
+        let is_single = self.fits_in_one_message();
+        let full_content = self.header.clone() + &self.content;
+
+        let contents = if is_single {
+            vec![full_content]
+        } else {
+            split_message(&full_content, MESSAGE_CHARACTER_LIMIT)
+        };
+
+        let mut attachments = self.attachments;
+        let contents = if contents.len() <= MAX_SPLIT_MESSAGES {
+            contents
+        } else {
+            // 分割しても収まらない場合はファイル添付に切り替える
+            attachments.push(CreateAttachment::bytes(self.content, FALLBACK_FILENAME));
+            vec![format!("{}Reactions:\n  📎: {}\n", self.header, FALLBACK_FILENAME)]
+        };
+
+        contents
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| {
+                let reply = CreateReply::default().content(content);
+                if index == 0 {
+                    attachments.drain(..).fold(reply, |reply, attachment| reply.attachment(attachment))
+                } else {
+                    reply
+                }
+            })
+            .collect()
+    }
+
+    fn into_embed_replies(self) -> Vec<CreateReply> {
+        let content = self.header + &self.content;
+        let mut attachments = self.attachments;
+
+        self.embeds
+            .into_iter()
+            .enumerate()
+            .map(|(index, embeds)| {
+                let reply = CreateReply { embeds, ..Default::default() };
+                if index == 0 {
+                    let reply = if content.is_empty() { reply } else { reply.content(content.clone()) };
+                    attachments.drain(..).fold(reply, |reply, attachment| reply.attachment(attachment))
+                } else {
+                    reply
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Text report of the default message with every option off
+    pub(crate) fn parameter() -> ReactionUsersParameter {
+        ReactionUsersParameter::for_emojis(&Message::default(), [])
+    }
+
+    fn mentions(count: usize) -> String {
+        (0..count)
+            .map(|i| format!("<@{}>", 100_000_000_000_000_000u64 + i as u64))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_into_replies_single_message() {
+        let response = ReactionUsersResponse::text("header\n".to_string(), "body".to_string());
+        assert!(response.fits_in_one_message());
+        let replies = response.into_replies();
+
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].content.as_deref(), Some("header\nbody"));
+    }
+
+    #[test]
+    fn test_into_replies_splits_oversize_output() {
+        let users = mentions(60);
+        let content = format!("Reactions:\n{}\n```{}```\n", users, users);
+        let response = ReactionUsersResponse::text("header\n".to_string(), content);
+        assert!(!response.fits_in_one_message());
+        let replies = response.into_replies();
+
+        assert!(replies.len() > 1);
+        assert!(replies[0].content.as_deref().unwrap().starts_with("header\n"));
+        for reply in &replies {
+            assert!(reply.content.as_deref().unwrap().chars().count() <= MESSAGE_CHARACTER_LIMIT);
+        }
+    }
+
+    #[test]
+    fn test_into_replies_sends_embed_groups() {
+        let mut response = ReactionUsersResponse::text(String::new(), String::new());
+        response.embeds = vec![
+            vec![CreateEmbed::new().title("a"), CreateEmbed::new().title("b")],
+            vec![CreateEmbed::new().title("c")],
+        ];
+        let replies = response.into_replies();
+
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].embeds.len(), 2);
+        assert_eq!(replies[1].embeds.len(), 1);
+        assert!(replies[0].content.is_none());
+    }
+
+    #[test]
+    fn test_into_replies_falls_back_to_file() {
+        let users = mentions(500);
+        let content = format!("Reactions:\n{}\n```{}```\n", users, users);
+        let response = ReactionUsersResponse::text("header\n".to_string(), content);
+        let replies = response.into_replies();
+
+        assert_eq!(replies.len(), 1);
+        assert!(replies[0].content.as_deref().unwrap().starts_with("header\n"));
+        assert_eq!(replies[0].attachments.len(), 1);
+        assert_eq!(replies[0].attachments[0].filename, FALLBACK_FILENAME);
+    }
+}
@@ -1,15 +1,21 @@
-use std::collections::HashMap;
-use poise::serenity_prelude::{Message, MessageReaction, User};
+use std::collections::HashSet;
+use poise::serenity_prelude::{Http, Message, MessageReaction, User, UserId};
 
-/// Retrieves a mapping of reaction emojis to the users who reacted to them, while allowing certain reactions to be excluded.
+use crate::services::reaction_users::filter::{ReactionFilter, RoleFilter, UserFilter};
+use crate::services::reaction_users::report::FetchedReaction;
+use crate::utils::discord_helper::fetch_guild_members;
+
+/// Retrieves the reaction emojis paired with the users who reacted to them, while allowing certain reactions to be filtered out.
+///
+/// The pairs keep Discord's reaction order, so repeated calls list the emojis in the same order.
 ///
 /// # Parameters
 /// - `ctx`: A reference to the [`Context`] which provides access to the Discord API and cache.
 /// - `message`: A reference to the [`Message`] for which the reaction information is being retrieved.
-/// - `exclude_reactions`: A slice of [`String`]s representing the emojis to exclude from the result.
+/// - `filter`: A [`ReactionFilter`] selecting the reactions to include in the result.
 ///
 /// # Returns
-/// - On success, the `Ok` variant contains the mapping of reaction emojis to users.
+/// - On success, the `Ok` variant contains the `(emoji, users)` pairs in reaction order.
 /// - On failure, the `Err` variant may contain an error specifying what went wrong.
 ///
 /// # Errors
@@ -17,12 +23,12 @@ use poise::serenity_prelude::{Message, MessageReaction, User};
 ///
 /// # Example
 /// ```rust
-/// let exclude_reactions = vec![":thumbs up:".to_string()];
-/// let reaction_data = reaction_users(&ctx, &message, &exclude_reactions).await;
+/// let filter = ReactionFilter::parse(None, Some(":thumbsup:")).await;
+/// let reactions = to_reaction_list(ctx, &message, &filter).await;
 ///
-/// match reaction_data {
-///     Ok(data) => {
-///         for (emoji, users) in data {
+/// match reactions {
+///     Ok(reactions) => {
+///         for (emoji, users) in reactions {
 ///             println!("Emoji: {}, Users: {:?}", emoji, users);
 ///         }
 ///     }
@@ -31,8 +37,9 @@ use poise::serenity_prelude::{Message, MessageReaction, User};
 /// ```
 ///
 /// # Notes
-/// - This function internally uses `get_filtered_users2` to fetch the users who reacted with a specific emoji.
-/// - Any reactions listed in the `exclude_reactions` parameter are ignored and not included in the output.
+/// - This function uses [`fetch_reactions`] and drops the reactions whose users could not be fetched.
+///   Use [`fetch_reactions`] directly when those failures must be reported or told apart from an empty reaction.
+/// - Reactions rejected by `filter` are ignored and not included in the output.
 ///
 /// # Dependencies
 /// - The function depends on the `log` crate for error logging.
@@ -40,26 +47,20 @@ use poise::serenity_prelude::{Message, MessageReaction, User};
 ///
 /// [`Context`]: https://docs.rs/serenity/*/serenity/model/prelude/struct.Context.html
 /// [`Message`]: https://docs.rs/serenity/*/serenity/model/prelude/struct.Message.html
-/// [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
 /// [`User`]: https://docs.rs/serenity/*/serenity/model/user/struct.User.html
-pub async fn to_reaction_map(
+pub async fn to_reaction_list(
     ctx: crate::Context<'_>,
     message: &Message,
-    exclude_reactions: &[String]
-) -> Result<HashMap<String, Vec<User>>, crate::Error> {
-    let mut result: HashMap<String, Vec<User>> = HashMap::new();
+    filter: &ReactionFilter
+) -> Result<Vec<(String, Vec<User>)>, crate::Error> {
+    let mut result: Vec<(String, Vec<User>)> = Vec::new();
 
-    for reaction in &message.reactions {
+    for reaction in fetch_reactions(ctx.http(), message, filter).await {
         let reaction_emoji = reaction.reaction_type.to_string();
 
-        // Skip excluded reactions
-        if exclude_reactions.contains(&reaction_emoji) {
-            continue;
-        }
-
-        match fetch_reaction_users(ctx, &message, reaction).await {
+        match reaction.users {
             Ok(users) => {
-                result.insert(reaction_emoji, users);
+                result.push((reaction_emoji, users));
             }
             Err(e) => {
                 log::error!("Failed to get users to reaction {}: {}", reaction_emoji, e);
@@ -69,6 +70,102 @@ pub async fn to_reaction_map(
     Ok(result)
 }
 
+/// Fetches the users of every reaction on `message`, keeping Discord's reaction order.
+///
+/// Unlike [`to_reaction_list`], a failed fetch does not drop the reaction silently:
+/// the error is kept in [`FetchedReaction::users`] so that it can be reported.
+pub async fn fetch_reactions(
+    http: &Http,
+    message: &Message,
+    filter: &ReactionFilter
+) -> Vec<FetchedReaction> {
+    let mut result = Vec::new();
+
+    for reaction in &message.reactions {
+        // Skip excluded reactions
+        if !filter.is_match(&reaction.reaction_type) {
+            continue;
+        }
+
+        let users = fetch_reaction_users(http, message, reaction)
+            .await
+            .map_err(|e| e.to_string());
+
+        result.push(FetchedReaction {
+            reaction_type: reaction.reaction_type.clone(),
+            count: reaction.count,
+            users,
+        });
+    }
+    result
+}
+
+/// Drops the reactors that do not pass `user_filter` (bots, the invoking user).
+pub fn filter_reactions_by_user(user_filter: &UserFilter, fetched: &mut [FetchedReaction]) {
+    for reaction in fetched.iter_mut() {
+        if let Ok(users) = reaction.users.as_mut() {
+            users.retain(|user| user_filter.is_match(user));
+        }
+    }
+}
+
+/// Drops the reactors that do not pass `role_filter`, based on their server roles.
+///
+/// Users that cannot be resolved as members (they left the server, or the bot cannot see it)
+/// are dropped when an include role is given and kept otherwise.
+///
+/// # Returns
+/// Warnings to show alongside the report.
+///
+/// # Errors
+/// Returns an error when the message is not in a server.
+pub async fn filter_reactions_by_role(
+    ctx: crate::Context<'_>,
+    message: &Message,
+    role_filter: &RoleFilter,
+    fetched: &mut [FetchedReaction],
+) -> Result<Vec<String>, crate::Error> {
+    // HTTPで取得したメッセージにはguild_idが含まれないため、コマンド実行元のサーバーを使う
+    let guild_id = message
+        .guild_id
+        .or(ctx.guild_id())
+        .ok_or("Role filters can only be used on messages in a server.")?;
+
+    let user_ids: Vec<UserId> = fetched
+        .iter()
+        .filter_map(|reaction| reaction.users.as_ref().ok())
+        .flatten()
+        .map(|user| user.id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let members = fetch_guild_members(ctx, guild_id, &user_ids).await;
+
+    let mut unresolved: HashSet<UserId> = HashSet::new();
+    for reaction in fetched.iter_mut() {
+        if let Ok(users) = reaction.users.as_mut() {
+            users.retain(|user| match members.get(&user.id) {
+                Some(Ok(member)) => role_filter.is_match(guild_id, &member.roles),
+                _ => {
+                    unresolved.insert(user.id);
+                    role_filter.include.is_none()
+                }
+            });
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if !unresolved.is_empty() {
+        warnings.push(format!(
+            "{} users could not be resolved as server members and were {}.",
+            unresolved.len(),
+            if role_filter.include.is_some() { "excluded" } else { "kept" },
+        ));
+    }
+    Ok(warnings)
+}
+
 /// Fetches and retrieves a list of users who reacted with a specific reaction to a given message.
 ///
 /// This function uses the Discord API to iterate through all pages of users who reacted
@@ -76,12 +173,12 @@ pub async fn to_reaction_map(
 /// of 100 users per request) until all users have been retrieved.
 ///
 /// # Parameters:
-/// - `ctx`: A reference to the [`serenity::prelude::Context`] containing the bot's context,
-///          including HTTP client and cache.
+/// - `http`: The HTTP client, so that this can also be called outside of a command
+///   (e.g. by the giveaway scheduler).
 /// - `message`: A reference to the [`serenity::model::channel::Message`] that contains the specific
-///              reaction to fetch users from.
+///   reaction to fetch users from.
 /// - `reaction`: A reference to the [`serenity::model::channel::MessageReaction`] specifying the
-///               reaction type to filter users by.
+///   reaction type to filter users by.
 ///
 /// # Returns:
 /// An asynchronous result containing a vector of [`serenity::model::user::User`] objects if successful.
@@ -98,11 +195,11 @@ pub async fn to_reaction_map(
 ///
 /// # Example:
 /// ```rust
+/// # use serenity::http::Http;
 /// # use serenity::model::user::User;
-/// # use serenity::client::Context;
 /// # use serenity::model::channel::{Message, MessageReaction};
-/// # async fn example(ctx: &Context, message: &Message, reaction: &MessageReaction) -> Result<(), Box<dyn std::error::Error>> {
-/// let users: Vec<User> = get_filtered_users2(ctx, message, reaction).await?;
+/// # async fn example(http: &Http, message: &Message, reaction: &MessageReaction) -> Result<(), Box<dyn std::error::Error>> {
+/// let users: Vec<User> = fetch_reaction_users(http, message, reaction).await?;
 /// println!("Number of users who reacted: {}", users.len());
 /// # Ok(())
 /// # }
@@ -110,8 +207,8 @@ pub async fn to_reaction_map(
 ///
 /// # Notes:
 /// - Users are fetched and paginated through the Discord API using the `reaction_users` method.
-async fn fetch_reaction_users(
-    ctx: crate::Context<'_>,
+pub async fn fetch_reaction_users(
+    http: &Http,
     message: &Message,
     reaction: &MessageReaction,
 ) -> Result<Vec<User>, crate::Error> {
@@ -124,7 +221,7 @@ async fn fetch_reaction_users(
         let users_page = message
             .channel_id
             .reaction_users(
-                &ctx.http(),
+                http,
                 message.id,
                 reaction.reaction_type.clone(),
                 Some(100), // Limit per request (max 100)
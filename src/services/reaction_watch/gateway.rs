@@ -0,0 +1,90 @@
+use poise::serenity_prelude::{FullEvent, MessageId, Reaction, Timestamp, UserId};
+
+use crate::services::reaction_watch::signup::SignupPromotion;
+use crate::services::reaction_watch::timeline::MAX_EVENTS_PER_WATCH;
+use crate::services::reaction_watch::{ReactionEvent, ReactionEventKind, WatchStore};
+
+impl ReactionEvent {
+    /// The event of a gateway reaction, or `None` for other events.
+    ///
+    /// Returns the ID of the message the event belongs to.
+    pub fn from_gateway(event: &FullEvent, at: i64) -> Option<(MessageId, Self)> {
+        let from_reaction = |kind: ReactionEventKind, reaction: &Reaction, with_user: bool| {
+            (reaction.message_id, Self {
+                kind,
+                at,
+                user_id: reaction.user_id.filter(|_| with_user),
+                emoji: Some(reaction.emoji.to_string()),
+            })
+        };
+
+        match event {
+            // 他のBotのリアクションは開始時と同じく記録しない
+            FullEvent::ReactionAdd { add_reaction }
+                if add_reaction.member.as_ref().is_some_and(|member| member.user.bot) => None,
+            FullEvent::ReactionAdd { add_reaction } => {
+                Some(from_reaction(ReactionEventKind::Add, add_reaction, true))
+            }
+            FullEvent::ReactionRemove { removed_reaction } => {
+                Some(from_reaction(ReactionEventKind::Remove, removed_reaction, true))
+            }
+            FullEvent::ReactionRemoveEmoji { removed_reactions } => {
+                Some(from_reaction(ReactionEventKind::RemoveEmoji, removed_reactions, false))
+            }
+            FullEvent::ReactionRemoveAll { removed_from_message_id, .. } => {
+                Some((*removed_from_message_id, Self { kind: ReactionEventKind::RemoveAll, at, user_id: None, emoji: None }))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Records a gateway reaction event when it belongs to a watched message.
+///
+/// Events of other messages, reactions of `bot_id` itself and reactions added by other bots
+/// are ignored without touching the file.
+/// Returns the waitlisted users who got a spot when the message is a sign-up that notifies them.
+pub async fn record_reaction_event(
+    store: &WatchStore,
+    event: &FullEvent,
+    bot_id: UserId,
+) -> Result<Option<SignupPromotion>, crate::Error> {
+    let Some((message_id, event)) = ReactionEvent::from_gateway(event, Timestamp::now().unix_timestamp()) else {
+        return Ok(None);
+    };
+    if event.user_id == Some(bot_id) {
+        return Ok(None);
+    }
+
+    let mut watches = store.lock().await;
+    let Some(watch) = watches.get_mut(&message_id) else {
+        return Ok(None);
+    };
+
+    let before = watch.signup_sheet().filter(|_| watch.signup.as_ref().is_some_and(|signup| signup.notify));
+    watch.events.push(event);
+    let promotion = before
+        .zip(watch.signup_sheet())
+        .map(|(before, after)| before.promoted_to(&after))
+        .filter(|users| !users.is_empty())
+        .map(|users| SignupPromotion { channel_id: watch.channel_id, message_id, users });
+    if watch.events.len() > MAX_EVENTS_PER_WATCH {
+        watch.compact_events();
+    }
+
+    store.save(&watches)?;
+    Ok(promotion)
+}
+
+/// Stops watching messages that were deleted, dropping their recorded events.
+///
+/// Other messages are ignored without touching the file.
+pub async fn forget_deleted_messages(store: &WatchStore, message_ids: &[MessageId]) -> Result<(), crate::Error> {
+    let mut watches = store.lock().await;
+    let count = watches.len();
+    watches.retain(|message_id, _| !message_ids.contains(message_id));
+    if watches.len() != count {
+        store.save(&watches)?;
+    }
+    Ok(())
+}
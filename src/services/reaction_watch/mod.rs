@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use poise::serenity_prelude::{
+    ChannelId, GuildId, Message, MessageId, Timestamp, UserId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::services::reaction_users::filter::ReactionFilter;
+use crate::services::reaction_users::utils::to_reaction_list;
+use crate::services::reaction_watch::signup::SignupConfig;
+use crate::utils::json_store::JsonStore;
+
+pub mod gateway;
+pub mod render;
+pub mod signup;
+pub mod timeline;
+
+pub use gateway::{forget_deleted_messages, record_reaction_event};
+pub use render::{get_signup_text, get_watch_timeline_text, to_watch_list_text};
+pub use timeline::TimedReactor;
+
+/// Watched messages with their recorded reaction events, saved across restarts
+pub type WatchStore = JsonStore<HashMap<MessageId, WatchedMessage>>;
+
+/// How many messages one server can watch at the same time
+pub const MAX_WATCHES_PER_GUILD: usize = 25;
+
+/// A message whose reaction events are recorded as they arrive from the gateway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedMessage {
+    pub message_id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub started_by: UserId,
+    /// Seconds since the Unix epoch
+    pub started_at: i64,
+    /// Events in the order they were received
+    pub events: Vec<ReactionEvent>,
+    /// First-come-first-served sign-up settings, when the message is a sign-up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signup: Option<SignupConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReactionEvent {
+    pub kind: ReactionEventKind,
+    /// Seconds since the Unix epoch, when the bot received the event
+    pub at: i64,
+    /// The user who added or removed the reaction; `None` for removals by a moderator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+    /// The emoji as `reaction_type.to_string()`; `None` when every reaction was removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionEventKind {
+    /// A user reacted. Reactions present when the watch started are recorded at the start time.
+    Add,
+    /// A user removed their reaction
+    Remove,
+    /// Every reaction of the message was removed
+    RemoveAll,
+    /// Every reaction with one emoji was removed
+    RemoveEmoji,
+}
+
+impl WatchedMessage {
+    pub fn message_url(&self) -> String {
+        self.message_id.link(self.channel_id, self.guild_id)
+    }
+}
+
+/// Starts watching `message`. The current reactors are recorded at the start time,
+/// in Discord's order, since the API does not tell when they reacted.
+///
+/// A user who reacts while the current reactors are fetched may be listed as
+/// reacting before the watch. Reactions of bots are not recorded.
+///
+/// Returns the number of current reactions recorded.
+///
+/// # Errors
+/// Returns an error when the message is already watched or the server watches too many messages.
+pub async fn start_watch(
+    ctx: crate::Context<'_>,
+    message: &Message,
+    signup: Option<SignupConfig>,
+) -> Result<usize, crate::Error> {
+    let store = &ctx.data().watches;
+    let guild_id = message.guild_id.or(ctx.guild_id());
+
+    let started_at = Timestamp::now().unix_timestamp();
+    let watch = WatchedMessage {
+        message_id: message.id,
+        channel_id: message.channel_id,
+        guild_id,
+        started_by: ctx.author().id,
+        started_at,
+        events: Vec::new(),
+        signup,
+    };
+
+    {
+        let mut watches = store.lock().await;
+        if watches.contains_key(&message.id) {
+            return Err("This message is already watched.".into());
+        }
+        if watches.values().filter(|watch| watch.guild_id == guild_id).count() >= MAX_WATCHES_PER_GUILD {
+            return Err(format!("This server already watches {} messages. Stop watching one first.", MAX_WATCHES_PER_GUILD).into());
+        }
+        watches.insert(message.id, watch);
+        store.save(&watches)?;
+    }
+
+    // 取得中に届いたイベントも記録されるよう、登録してから現在のリアクションを取得する
+    let current = match to_reaction_list(ctx, message, &ReactionFilter::default()).await {
+        Ok(current) => current,
+        Err(e) => {
+            let mut watches = store.lock().await;
+            watches.remove(&message.id);
+            store.save(&watches)?;
+            return Err(e);
+        }
+    };
+    let existing: Vec<ReactionEvent> = current
+        .iter()
+        .flat_map(|(emoji, users)| users.iter().filter(|user| !user.bot).map(move |user| ReactionEvent {
+            kind: ReactionEventKind::Add,
+            at: started_at,
+            user_id: Some(user.id),
+            emoji: Some(emoji.clone()),
+        }))
+        .collect();
+    let count = existing.len();
+
+    let mut watches = store.lock().await;
+    if let Some(watch) = watches.get_mut(&message.id) {
+        watch.events.splice(0..0, existing);
+    }
+    store.save(&watches)?;
+
+    Ok(count)
+}
+
+/// Stops watching `message_id` and deletes its recorded events.
+///
+/// # Errors
+/// Returns an error when the message is not watched in this server.
+pub async fn stop_watch(store: &WatchStore, guild_id: Option<GuildId>, message_id: MessageId) -> Result<String, crate::Error> {
+    let mut watches = store.lock().await;
+    let watch = watches
+        .get(&message_id)
+        .filter(|watch| watch.guild_id == guild_id)
+        .ok_or("This message is not watched.")?;
+    let url = watch.message_url();
+    let count = watch.events.len();
+
+    watches.remove(&message_id);
+    store.save(&watches)?;
+    Ok(format!("Stopped watching {} and deleted {} recorded events.", url, count))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn event(kind: ReactionEventKind, at: i64, user_id: Option<u64>, emoji: Option<&str>) -> ReactionEvent {
+        ReactionEvent { kind, at, user_id: user_id.map(UserId::new), emoji: emoji.map(str::to_string) }
+    }
+
+    pub(crate) fn watch(events: Vec<ReactionEvent>) -> WatchedMessage {
+        WatchedMessage {
+            message_id: MessageId::new(1),
+            channel_id: ChannelId::new(2),
+            guild_id: Some(GuildId::new(3)),
+            started_by: UserId::new(4),
+            started_at: 100,
+            events,
+            signup: None,
+        }
+    }
+
+
+}
@@ -0,0 +1,125 @@
+use poise::serenity_prelude::Mentionable;
+
+use crate::services::reaction_watch::signup::SignupSheet;
+use crate::services::reaction_watch::{TimedReactor, WatchedMessage, MAX_WATCHES_PER_GUILD};
+
+pub fn to_watch_list_text(watches: &[&WatchedMessage]) -> String {
+    if watches.is_empty() {
+        return "No messages are watched in this server.".to_string();
+    }
+
+    let lines: String = watches
+        .iter()
+        .map(|watch| format!(
+            "- {} since <t:{}:f> by {} ({} events{})\n",
+            watch.message_url(),
+            watch.started_at,
+            watch.started_by.mention(),
+            watch.events.len(),
+            watch.signup.as_ref().map(|signup| format!(", sign-up for {}", signup.capacity)).unwrap_or_default(),
+        ))
+        .collect();
+    format!("👀 Watched messages ({}/{}):\n{}", watches.len(), MAX_WATCHES_PER_GUILD, lines)
+}
+
+/// Lists the current reactors of each emoji in the order they reacted, with the time.
+pub fn get_watch_timeline_text(watch: &WatchedMessage) -> String {
+    let timeline = watch.timeline();
+    let reactions: String = if timeline.is_empty() {
+        "  No one reacted.\n".to_string()
+    } else {
+        timeline
+            .iter()
+            .map(|(emoji, reactors)| {
+                let lines: String = reactors
+                    .iter()
+                    .enumerate()
+                    .map(|(index, reactor)| {
+                        let time = if reactor.at <= watch.started_at {
+                            "before the watch".to_string()
+                        } else {
+                            format!("<t:{}:f>", reactor.at)
+                        };
+                        format!("    {}. {} {}\n", index + 1, reactor.user_id.mention(), time)
+                    })
+                    .collect();
+                format!("  {}: {}\n{}", emoji, reactors.len(), lines)
+            })
+            .collect()
+    };
+
+    format!(
+        "👀 Reaction timeline\n  📝: {}\n  🕒: Watched since <t:{}:f>, {} events\n\nReactions:\n{}",
+        watch.message_url(),
+        watch.started_at,
+        watch.events.len(),
+        reactions,
+    )
+}
+
+pub fn get_signup_text(watch: &WatchedMessage, sheet: &SignupSheet) -> String {
+    let lines = |reactors: &[TimedReactor]| -> String {
+        if reactors.is_empty() {
+            return "  -\n".to_string();
+        }
+        reactors
+            .iter()
+            .enumerate()
+            .map(|(index, reactor)| {
+                let time = if reactor.at <= watch.started_at {
+                    "before the watch".to_string()
+                } else {
+                    format!("<t:{}:f>", reactor.at)
+                };
+                format!("  {}. {} {}\n", index + 1, reactor.user_id.mention(), time)
+            })
+            .collect()
+    };
+    let emoji = watch.signup.as_ref().and_then(|config| config.emoji.clone()).unwrap_or_else(|| "any reaction".to_string());
+
+    format!(
+        "🎟️ Sign-up\n  📝: {}\n  🔖: {}\n\nConfirmed ({}/{}):\n{}\nWaitlist ({}):\n{}",
+        watch.message_url(),
+        emoji,
+        sheet.confirmed.len(),
+        sheet.capacity,
+        lines(&sheet.confirmed),
+        sheet.waitlist.len(),
+        lines(&sheet.waitlist),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_watch::tests::{event, watch};
+    use crate::services::reaction_watch::signup::SignupConfig;
+    use crate::services::reaction_watch::ReactionEventKind;
+
+    #[test]
+    fn test_watch_timeline_text() {
+        let watch = watch(vec![
+            event(ReactionEventKind::Add, 100, Some(10), Some("✅")),
+            event(ReactionEventKind::Add, 160, Some(11), Some("✅")),
+        ]);
+
+        let text = get_watch_timeline_text(&watch);
+
+        assert!(text.contains("2 events"));
+        assert!(text.contains("  ✅: 2\n    1. <@10> before the watch\n    2. <@11> <t:160:f>\n"));
+    }
+
+    #[test]
+    fn test_signup_text() {
+        let mut watch = watch(vec![
+            event(ReactionEventKind::Add, 100, Some(10), Some("✅")),
+            event(ReactionEventKind::Add, 150, Some(11), Some("✅")),
+        ]);
+        watch.signup = Some(SignupConfig { capacity: 1, emoji: Some("✅".to_string()), notify: true });
+
+        let text = get_signup_text(&watch, &watch.signup_sheet().unwrap());
+
+        assert!(text.contains("Confirmed (1/1):\n  1. <@10> before the watch\n"));
+        assert!(text.contains("Waitlist (1):\n  1. <@11> <t:150:f>\n"));
+    }
+}
@@ -0,0 +1,255 @@
+use poise::serenity_prelude::{
+    ChannelId, CreateAllowedMentions, CreateMessage, Http, Mentionable, Message, MessageId, UserId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::services::reaction_users::filter::ReactionMatcher;
+use crate::services::reaction_watch::{start_watch, TimedReactor, WatchedMessage};
+
+/// First-come-first-served sign-up on a watched message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignupConfig {
+    /// Number of confirmed spots
+    pub capacity: usize,
+    /// Only reactions with this emoji sign up; `None` counts any reaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+    /// Mention waitlisted users in the channel when they get a spot
+    #[serde(default)]
+    pub notify: bool,
+}
+
+/// Sign-up state, replayed from the recorded events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignupSheet {
+    pub capacity: usize,
+    /// The first `capacity` users, in the order they reacted
+    pub confirmed: Vec<TimedReactor>,
+    /// Everyone after them, in the order they reacted
+    pub waitlist: Vec<TimedReactor>,
+}
+
+/// Waitlisted users who got a spot, to be notified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignupPromotion {
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub users: Vec<UserId>,
+}
+
+impl WatchedMessage {
+    /// The sign-up state, or `None` when the message is not a sign-up.
+    pub fn signup_sheet(&self) -> Option<SignupSheet> {
+        let config = self.signup.as_ref()?;
+
+        // 複数の絵文字でリアクションした人は、最も早いリアクションで並べる
+        let signup_matcher = config.emoji.as_deref().map(ReactionMatcher::parse);
+        let mut reactors: Vec<TimedReactor> = Vec::new();
+        for (emoji, timed) in self.timeline() {
+            if signup_matcher.as_ref().is_some_and(|matcher| *matcher != ReactionMatcher::parse(&emoji)) {
+                continue;
+            }
+            for reactor in timed {
+                match reactors.iter_mut().find(|other| other.user_id == reactor.user_id) {
+                    Some(other) if reactor.sequence < other.sequence => *other = reactor,
+                    Some(_) => {}
+                    None => reactors.push(reactor),
+                }
+            }
+        }
+        reactors.sort_by_key(|reactor| reactor.sequence);
+
+        let waitlist = reactors.split_off(config.capacity.min(reactors.len()));
+        Some(SignupSheet { capacity: config.capacity, confirmed: reactors, waitlist })
+    }
+}
+
+impl WatchedMessage {
+    /// Number of sign-up reactors that were already there when the watch started.
+    ///
+    /// Discord does not tell when they reacted, so they cannot be ranked.
+    fn signup_reactors_before_watch(&self) -> usize {
+        self.signup_sheet()
+            .map(|sheet| {
+                sheet.confirmed
+                    .iter()
+                    .chain(&sheet.waitlist)
+                    .filter(|reactor| reactor.at <= self.started_at)
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+}
+
+impl SignupSheet {
+    /// Users who were waitlisted in `self` and are confirmed in `after`.
+    ///
+    /// New reactors who got a free spot right away are not promotions.
+    pub fn promoted_to(&self, after: &SignupSheet) -> Vec<UserId> {
+        after
+            .confirmed
+            .iter()
+            .map(|reactor| reactor.user_id)
+            .filter(|user_id| self.waitlist.iter().any(|reactor| reactor.user_id == *user_id))
+            .collect()
+    }
+}
+
+/// Makes `message` a sign-up, watching it when it is not watched yet.
+///
+/// Changing the settings of a sign-up keeps its recorded reactions.
+///
+/// # Errors
+/// Returns an error when the emoji is a custom emoji given by name, when the message cannot be watched,
+/// or when someone had signed up before the message was watched. Their reaction times are unknown,
+/// so a new watch is removed again rather than ranking them by user ID.
+pub async fn start_signup(
+    ctx: crate::Context<'_>,
+    message: &Message,
+    config: SignupConfig,
+) -> Result<String, crate::Error> {
+    if let Some(emoji) = &config.emoji
+        && let ReactionMatcher::Name(_) = ReactionMatcher::parse(emoji.trim())
+    {
+        return Err("Give custom emojis as `<:name:id>`, e.g. by picking them in the emoji menu.".into());
+    }
+
+    let store = &ctx.data().watches;
+    let updated = {
+        let mut watches = store.lock().await;
+        match watches.get_mut(&message.id).filter(|watch| watch.guild_id == message.guild_id.or(ctx.guild_id())) {
+            Some(watch) => {
+                let previous = watch.signup.replace(config.clone());
+                let count = watch.signup_reactors_before_watch();
+                if count > 0 {
+                    watch.signup = previous;
+                    return Err(to_reacted_before_error(count));
+                }
+                store.save(&watches)?;
+                true
+            }
+            None => false,
+        }
+    };
+    if !updated {
+        start_watch(ctx, message, Some(config)).await?;
+
+        // 開始前のリアクションは順番が分からないため、受付を取り消す
+        let mut watches = store.lock().await;
+        let count = watches.get(&message.id).map(WatchedMessage::signup_reactors_before_watch).unwrap_or(0);
+        if count > 0 {
+            watches.remove(&message.id);
+            store.save(&watches)?;
+            return Err(to_reacted_before_error(count));
+        }
+    }
+
+    let watches = store.lock().await;
+    let watch = watches.get(&message.id).ok_or("The sign-up was stopped while it started.")?;
+    let sheet = watch.signup_sheet().ok_or("The sign-up was changed while it started.")?;
+    Ok(format!(
+        "🎟️ Sign-up {} for {}: {}/{} confirmed, {} waitlisted.",
+        if updated { "updated" } else { "started" },
+        watch.message_url(),
+        sheet.confirmed.len(),
+        sheet.capacity,
+        sheet.waitlist.len(),
+    ))
+}
+
+fn to_reacted_before_error(count: usize) -> crate::Error {
+    format!(
+        "{} people had already reacted before the sign-up. Discord does not tell when they reacted, \
+         so they cannot be ranked fairly. Post the sign-up on a new message instead.",
+        count,
+    )
+    .into()
+}
+
+/// Tells promoted users in the sign-up's channel, as a reply to the sign-up message.
+pub async fn notify_promotion(http: &Http, promotion: &SignupPromotion) -> Result<(), crate::Error> {
+    let mentions = promotion.users.iter().map(|id| id.mention().to_string()).collect::<Vec<String>>().join(" ");
+    let message = CreateMessage::new()
+        .content(format!("🎟️ {} A spot opened up: you are now confirmed.", mentions))
+        .reference_message((promotion.channel_id, promotion.message_id))
+        .allowed_mentions(CreateAllowedMentions::new().users(promotion.users.clone()));
+
+    promotion.channel_id.send_message(http, message).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_watch::tests::{event, watch};
+    use crate::services::reaction_watch::ReactionEventKind::*;
+
+    fn signup(capacity: usize, emoji: Option<&str>) -> SignupConfig {
+        SignupConfig { capacity, emoji: emoji.map(str::to_string), notify: true }
+    }
+
+    fn user_ids(reactors: &[TimedReactor]) -> Vec<u64> {
+        reactors.iter().map(|reactor| reactor.user_id.get()).collect()
+    }
+
+    #[test]
+    fn test_signup_sheet_first_come_first_served() {
+        let mut watch = watch(vec![
+            event(Add, 100, Some(10), Some("✅")),
+            event(Add, 110, Some(11), Some("🎉")),
+            event(Add, 110, Some(12), Some("✅")),
+            event(Add, 120, Some(13), Some("✅")),
+            event(Add, 130, Some(11), Some("✅")),
+        ]);
+        watch.signup = Some(signup(2, Some(":white_check_mark:")));
+
+        let sheet = watch.signup_sheet().unwrap();
+
+        assert_eq!(user_ids(&sheet.confirmed), vec![10, 12]);
+        assert_eq!(user_ids(&sheet.waitlist), vec![13, 11]);
+
+        watch.signup = Some(signup(2, None));
+        let sheet = watch.signup_sheet().unwrap();
+        assert_eq!(user_ids(&sheet.confirmed), vec![10, 11]);
+        assert_eq!(user_ids(&sheet.waitlist), vec![12, 13]);
+    }
+
+    #[test]
+    fn test_signup_reactors_before_watch() {
+        // watch() starts at 100
+        let mut watch = watch(vec![
+            event(Add, 100, Some(10), Some("🎉")),
+            event(Add, 110, Some(11), Some("✅")),
+        ]);
+        watch.signup = Some(signup(2, Some("✅")));
+        assert_eq!(watch.signup_reactors_before_watch(), 0);
+
+        watch.signup = Some(signup(2, None));
+        assert_eq!(watch.signup_reactors_before_watch(), 1);
+    }
+
+    #[test]
+    fn test_signup_promotion() {
+        let mut watch = watch(vec![
+            event(Add, 100, Some(10), Some("✅")),
+            event(Add, 110, Some(11), Some("✅")),
+            event(Add, 120, Some(12), Some("✅")),
+        ]);
+        watch.signup = Some(signup(1, None));
+        let before = watch.signup_sheet().unwrap();
+
+        watch.events.push(event(Remove, 130, Some(10), Some("✅")));
+        let after = watch.signup_sheet().unwrap();
+
+        assert_eq!(before.promoted_to(&after), vec![UserId::new(11)]);
+        assert_eq!(user_ids(&after.waitlist), vec![12]);
+
+        // 空きに直接入った新しいリアクションは繰り上げではない
+        let mut watch = watch.clone();
+        watch.events = vec![event(Add, 100, Some(10), Some("✅"))];
+        watch.signup = Some(signup(2, None));
+        let before = watch.signup_sheet().unwrap();
+        watch.events.push(event(Add, 110, Some(11), Some("✅")));
+        assert!(before.promoted_to(&watch.signup_sheet().unwrap()).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use poise::serenity_prelude::UserId;
+
+use crate::services::reaction_users::filter::ReactionMatcher;
+use crate::services::reaction_watch::{ReactionEvent, ReactionEventKind, WatchedMessage};
+
+/// Number of recorded events above which a watch is compacted.
+///
+/// Every event rewrites the store, so the events of a busy message are replaced by the
+/// reactions they add up to instead of growing without limit.
+pub const MAX_EVENTS_PER_WATCH: usize = 1000;
+
+/// A current reactor of one emoji, with the time of their reaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedReactor {
+    pub user_id: UserId,
+    pub at: i64,
+    /// Position of the reaction among the recorded events, to order reactions of the same second
+    pub sequence: usize,
+}
+
+impl WatchedMessage {
+    /// Replays the events into the current reactors of each emoji, in the order they reacted.
+    ///
+    /// Emojis are ordered by their first recorded reaction; emojis without reactors are left out.
+    /// Each event's emoji is normalized once, so a replay stays cheap on a busy watch.
+    pub fn timeline(&self) -> Vec<(String, Vec<TimedReactor>)> {
+        let mut timeline: Vec<(String, Vec<TimedReactor>)> = Vec::new();
+        // timelineと同じ順の正規化した絵文字
+        let mut matchers: Vec<ReactionMatcher> = Vec::new();
+
+        for (sequence, event) in self.events.iter().enumerate() {
+            let matcher = event.emoji.as_deref().map(ReactionMatcher::parse);
+            let position = matcher.as_ref().and_then(|matcher| matchers.iter().position(|other| other == matcher));
+
+            match event.kind {
+                ReactionEventKind::Add => {
+                    let (Some(emoji), Some(matcher), Some(user_id)) = (&event.emoji, matcher, event.user_id) else {
+                        continue;
+                    };
+                    let position = position.unwrap_or_else(|| {
+                        timeline.push((emoji.clone(), Vec::new()));
+                        matchers.push(matcher);
+                        timeline.len() - 1
+                    });
+                    let reactors = &mut timeline[position].1;
+                    // 同じ絵文字の重複した追加は、最初の時刻を残す
+                    if !reactors.iter().any(|reactor| reactor.user_id == user_id) {
+                        reactors.push(TimedReactor { user_id, at: event.at, sequence });
+                    }
+                }
+                ReactionEventKind::Remove => {
+                    if let (Some(position), Some(user_id)) = (position, event.user_id) {
+                        timeline[position].1.retain(|reactor| reactor.user_id != user_id);
+                    }
+                }
+                ReactionEventKind::RemoveEmoji => {
+                    if let Some(position) = position {
+                        timeline[position].1.clear();
+                    }
+                }
+                ReactionEventKind::RemoveAll => {
+                    timeline.iter_mut().for_each(|(_, reactors)| reactors.clear());
+                }
+            }
+        }
+
+        timeline.retain(|(_, reactors)| !reactors.is_empty());
+        timeline
+    }
+
+    /// Replaces the events with one `Add` per current reactor, keeping their time and order.
+    ///
+    /// The timeline and the sign-up sheet stay the same; removed reactions are forgotten.
+    pub fn compact_events(&mut self) {
+        let mut reactors: Vec<(String, TimedReactor)> = self
+            .timeline()
+            .into_iter()
+            .flat_map(|(emoji, reactors)| reactors.into_iter().map(move |reactor| (emoji.clone(), reactor)))
+            .collect();
+        reactors.sort_by_key(|(_, reactor)| reactor.sequence);
+
+        self.events = reactors
+            .into_iter()
+            .map(|(emoji, reactor)| ReactionEvent {
+                kind: ReactionEventKind::Add,
+                at: reactor.at,
+                user_id: Some(reactor.user_id),
+                emoji: Some(emoji),
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::reaction_watch::tests::{event, watch};
+
+    fn reactors(timeline: &[(String, Vec<TimedReactor>)], index: usize) -> Vec<(u64, i64)> {
+        timeline[index].1.iter().map(|reactor| (reactor.user_id.get(), reactor.at)).collect()
+    }
+
+    #[test]
+    fn test_timeline_orders_by_reaction_time() {
+        use ReactionEventKind::*;
+        let watch = watch(vec![
+            event(Add, 100, Some(10), Some("✅")),
+            event(Add, 150, Some(11), Some("❌")),
+            event(Add, 200, Some(12), Some("✅")),
+            event(Remove, 250, Some(10), Some("✅")),
+            event(Add, 300, Some(10), Some("✅")),
+            event(Add, 350, Some(12), Some("✅")),
+        ]);
+
+        let timeline = watch.timeline();
+
+        assert_eq!(timeline.iter().map(|(emoji, _)| emoji.as_str()).collect::<Vec<&str>>(), vec!["✅", "❌"]);
+        assert_eq!(reactors(&timeline, 0), vec![(12, 200), (10, 300)]);
+        assert_eq!(reactors(&timeline, 1), vec![(11, 150)]);
+    }
+
+    #[test]
+    fn test_timeline_bulk_removals() {
+        use ReactionEventKind::*;
+        let watch = watch(vec![
+            event(Add, 100, Some(10), Some("<:party:55>")),
+            event(Add, 110, Some(11), Some("✅")),
+            event(RemoveEmoji, 120, None, Some("<a:party:55>")),
+            event(Add, 130, Some(12), Some("❌")),
+            event(RemoveAll, 140, None, None),
+            event(Add, 150, Some(13), Some("❌")),
+        ]);
+
+        let timeline = watch.timeline();
+
+        assert_eq!(timeline.len(), 1);
+        assert_eq!(timeline[0].0, "❌");
+        assert_eq!(reactors(&timeline, 0), vec![(13, 150)]);
+    }
+
+    #[test]
+    fn test_compact_events_keeps_timeline() {
+        use ReactionEventKind::*;
+        let mut watch = watch(vec![
+            event(Add, 100, Some(10), Some("✅")),
+            event(Add, 150, Some(11), Some("❌")),
+            event(Add, 200, Some(12), Some("✅")),
+            event(Remove, 250, Some(10), Some("✅")),
+            event(Add, 300, Some(10), Some("✅")),
+            event(Remove, 350, Some(11), Some("❌")),
+        ]);
+        let before = watch.timeline();
+
+        watch.compact_events();
+        let after = watch.timeline();
+
+        assert_eq!(watch.events.len(), 2);
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].0, before[0].0);
+        assert_eq!(reactors(&after, 0), reactors(&before, 0));
+    }
+}
@@ -0,0 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Environment variable that enables the privileged `GUILD_MEMBERS` gateway intent.
+pub const GUILD_MEMBERS_INTENT_ENV: &str = "DISCORD_GUILD_MEMBERS_INTENT";
+
+/// Whether the `GUILD_MEMBERS` intent is requested at startup.
+///
+/// The intent must also be enabled for the application in the Discord Developer Portal,
+/// otherwise the gateway connection is refused.
+pub fn is_guild_members_intent_enabled() -> bool {
+    env::var(GUILD_MEMBERS_INTENT_ENV)
+        .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Environment variable naming the directory where the bot keeps its state (giveaways, ...).
+pub const DATA_DIR_ENV: &str = "DISCORD_DATA_DIR";
+
+/// Directory where the bot keeps its state. Defaults to `data` in the working directory.
+pub fn data_dir() -> PathBuf {
+    env::var(DATA_DIR_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("data"))
+}
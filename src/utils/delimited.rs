@@ -0,0 +1,114 @@
+/// Field delimiter of a delimiter-separated values file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    pub fn as_char(&self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Tab => '\t',
+        }
+    }
+
+    /// File extension used for attachments in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "csv",
+            Delimiter::Tab => "tsv",
+        }
+    }
+}
+
+/// Characters that make spreadsheet software read a field as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Quotes a field when it contains the delimiter, a double quote or a line break.
+///
+/// Double quotes inside the field are escaped by doubling them (RFC 4180).
+/// A field starting like a formula, e.g. a username `=HYPERLINK(...)`, is prefixed with `'`
+/// so that spreadsheets show it as text instead of evaluating it.
+pub fn escape_field(field: &str, delimiter: Delimiter) -> String {
+    let field = if field.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+
+    let needs_quote = field.contains(delimiter.as_char())
+        || field.contains('"')
+        || field.contains('\n')
+        || field.contains('\r');
+
+    if needs_quote {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Serializes a header and rows into a delimiter-separated document.
+///
+/// Each line is terminated with CRLF so the output opens cleanly in spreadsheet software.
+pub fn to_delimited<R, F>(header: &[&str], rows: R, delimiter: Delimiter) -> String
+where
+    R: IntoIterator<Item = Vec<F>>,
+    F: AsRef<str>,
+{
+    let separator = delimiter.as_char().to_string();
+    let mut result = String::new();
+
+    result.push_str(&header.join(&separator));
+    result.push_str("\r\n");
+
+    for row in rows {
+        let line = row
+            .iter()
+            .map(|field| escape_field(field.as_ref(), delimiter))
+            .collect::<Vec<String>>()
+            .join(&separator);
+        result.push_str(&line);
+        result.push_str("\r\n");
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_field() {
+        assert_eq!(escape_field("plain", Delimiter::Comma), "plain");
+        assert_eq!(escape_field("a,b", Delimiter::Comma), "\"a,b\"");
+        assert_eq!(escape_field("a,b", Delimiter::Tab), "a,b");
+        assert_eq!(escape_field("a\tb", Delimiter::Tab), "\"a\tb\"");
+        assert_eq!(escape_field("say \"hi\"", Delimiter::Comma), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field("line\nbreak", Delimiter::Tab), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_escape_field_formula() {
+        assert_eq!(escape_field("=1+1", Delimiter::Comma), "'=1+1");
+        assert_eq!(escape_field("+81 90", Delimiter::Comma), "'+81 90");
+        assert_eq!(escape_field("-2", Delimiter::Comma), "'-2");
+        assert_eq!(escape_field("@SUM(A1)", Delimiter::Tab), "'@SUM(A1)");
+        assert_eq!(escape_field("=HYPERLINK(\"x\")", Delimiter::Comma), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(escape_field("\tcell", Delimiter::Tab), "\"'\tcell\"");
+        assert_eq!(escape_field("a=b", Delimiter::Comma), "a=b");
+    }
+
+    #[test]
+    fn test_to_delimited() {
+        let rows = vec![
+            vec!["👍", "1", "user,a"],
+            vec!["❤️", "2", "user_b"],
+        ];
+        let result = to_delimited(&["emoji", "user_id", "username"], rows, Delimiter::Comma);
+
+        assert_eq!(result, "emoji,user_id,username\r\n👍,1,\"user,a\"\r\n❤️,2,user_b\r\n");
+    }
+}
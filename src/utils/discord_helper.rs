@@ -1,8 +1,15 @@
 use std::collections::HashMap;
-use poise::serenity_prelude::{ChannelId, Http, Message, MessageId, User, UserId};
+use poise::futures_util::{stream, StreamExt};
+use poise::serenity_prelude::{ChannelId, GuildId, Http, Member, Message, MessageId, User, UserId};
 use crate::Context;
 use crate::utils::url_parser::{is_url, try_parse_discord_url, IdType};
 
+/// Number of member requests sent at the same time by [`fetch_guild_members`].
+///
+/// Serenity waits out the rate limit of the endpoint, so a few parallel requests only
+/// shorten the wait for large messages without causing errors.
+const MEMBER_FETCH_CONCURRENCY: usize = 5;
+
 #[allow(dead_code)]
 pub async fn fetch_discord_users(
     http: &Http,
@@ -16,6 +23,23 @@ pub async fn fetch_discord_users(
     results
 }
 
+/// Fetches the server member of each user.
+///
+/// Members are looked up in the cache first and fetched over HTTP otherwise.
+/// The cache only holds members when the `GUILD_MEMBERS` intent is enabled, so without it
+/// every user costs one request; the HTTP endpoint itself does not require the intent.
+/// Up to [`MEMBER_FETCH_CONCURRENCY`] requests are in flight at once.
+pub async fn fetch_guild_members(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    user_ids: &[UserId],
+) -> HashMap<UserId, Result<Member, poise::serenity_prelude::Error>> {
+    stream::iter(user_ids.iter().copied())
+        .map(|user_id| async move { (user_id, guild_id.member(ctx, user_id).await) })
+        .buffer_unordered(MEMBER_FETCH_CONCURRENCY)
+        .collect()
+        .await
+}
 
 /// Constructs a Discord message URL based on the provided `Message` object.
 ///
@@ -106,6 +130,6 @@ async fn get_message(ctx: Context<'_>, channel_id: ChannelId, message_id: Messag
     let http = ctx.http();
     match http.get_message(channel_id, message_id).await {
         Ok(message) => Ok(message),
-        Err(e) => return Err(format!("Failed to fetch message: {}", e))
+        Err(e) => Err(format!("Failed to fetch message: {}", e))
     }
 }
\ No newline at end of file
@@ -0,0 +1,189 @@
+use crate::utils::message_splitter::split_message;
+
+/// Maximum number of characters in an embed field value.
+pub const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+
+/// Maximum number of characters in an embed field name.
+pub const EMBED_FIELD_NAME_LIMIT: usize = 256;
+
+/// Maximum number of fields in one embed.
+pub const EMBED_FIELD_COUNT_LIMIT: usize = 25;
+
+/// Maximum number of characters across all embeds of one message.
+pub const EMBED_TOTAL_LIMIT: usize = 6000;
+
+/// Maximum number of embeds in one message.
+pub const EMBEDS_PER_MESSAGE_LIMIT: usize = 10;
+
+/// Suffix added to the names of fields and titles that continue a previous one.
+const CONTINUED_SUFFIX: &str = " (cont.)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+/// Content of one embed, before it is turned into a `CreateEmbed`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbedPage {
+    pub title: String,
+    pub description: String,
+    pub fields: Vec<EmbedField>,
+}
+
+impl EmbedField {
+    /// Splits a field whose value is too long into several fields.
+    ///
+    /// The value is cut at line breaks or spaces (see [`split_message`]),
+    /// and every field after the first gets a `(cont.)` suffix.
+    pub fn split(name: &str, value: &str, inline: bool) -> Vec<EmbedField> {
+        let name = truncate(name, EMBED_FIELD_NAME_LIMIT - CONTINUED_SUFFIX.len());
+
+        split_message(value, EMBED_FIELD_VALUE_LIMIT)
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| EmbedField {
+                name: if index == 0 { name.clone() } else { format!("{}{}", name, CONTINUED_SUFFIX) },
+                value,
+                inline,
+            })
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.name.chars().count() + self.value.chars().count()
+    }
+}
+
+impl EmbedPage {
+    /// Number of characters counted towards [`EMBED_TOTAL_LIMIT`]
+    pub fn len(&self) -> usize {
+        self.title.chars().count()
+            + self.description.chars().count()
+            + self.fields.iter().map(EmbedField::len).sum::<usize>()
+    }
+}
+
+/// Spreads `fields` over as many embeds as needed.
+///
+/// Each embed holds at most [`EMBED_FIELD_COUNT_LIMIT`] fields and [`EMBED_TOTAL_LIMIT`]
+/// characters. The description is only put on the first embed; the following embeds
+/// repeat the title with a `(cont.)` suffix.
+pub fn paginate_fields(title: &str, description: &str, fields: Vec<EmbedField>) -> Vec<EmbedPage> {
+    let mut pages = vec![EmbedPage {
+        title: title.to_string(),
+        description: description.to_string(),
+        fields: Vec::new(),
+    }];
+
+    for field in fields {
+        let page = pages.last_mut().unwrap();
+        let is_full = page.fields.len() >= EMBED_FIELD_COUNT_LIMIT
+            || page.len() + field.len() > EMBED_TOTAL_LIMIT;
+
+        if is_full && !page.fields.is_empty() {
+            pages.push(EmbedPage {
+                title: format!("{}{}", title, CONTINUED_SUFFIX),
+                description: String::new(),
+                fields: vec![field],
+            });
+        } else {
+            page.fields.push(field);
+        }
+    }
+
+    pages
+}
+
+/// Groups embeds into messages, keeping each message within
+/// [`EMBEDS_PER_MESSAGE_LIMIT`] embeds and [`EMBED_TOTAL_LIMIT`] characters.
+pub fn group_pages(pages: Vec<EmbedPage>) -> Vec<Vec<EmbedPage>> {
+    let mut messages: Vec<Vec<EmbedPage>> = Vec::new();
+    let mut length = 0;
+
+    for page in pages {
+        let fits = messages.last().is_some_and(|message| {
+            message.len() < EMBEDS_PER_MESSAGE_LIMIT && length + page.len() <= EMBED_TOTAL_LIMIT
+        });
+
+        if fits {
+            length += page.len();
+            messages.last_mut().unwrap().push(page);
+        } else {
+            length = page.len();
+            messages.push(vec![page]);
+        }
+    }
+
+    messages
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    text.chars().take(limit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mentions(count: usize) -> String {
+        (0..count)
+            .map(|i| format!("<@{}>", 100_000_000_000_000_000u64 + i as u64))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_split_field() {
+        let fields = EmbedField::split("✅ 100", &mentions(100), false);
+
+        assert!(fields.len() > 1);
+        assert_eq!(fields[0].name, "✅ 100");
+        assert_eq!(fields[1].name, "✅ 100 (cont.)");
+        for field in &fields {
+            assert!(field.value.chars().count() <= EMBED_FIELD_VALUE_LIMIT);
+            assert!(!field.value.starts_with(' '));
+        }
+        let joined = fields.iter().map(|f| f.value.as_str()).collect::<Vec<_>>().join(" ");
+        assert_eq!(joined, mentions(100));
+    }
+
+    #[test]
+    fn test_paginate_by_field_count() {
+        let fields = (0..30)
+            .map(|i| EmbedField { name: i.to_string(), value: "v".to_string(), inline: false })
+            .collect();
+
+        let pages = paginate_fields("Title", "desc", fields);
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].fields.len(), EMBED_FIELD_COUNT_LIMIT);
+        assert_eq!(pages[0].description, "desc");
+        assert_eq!(pages[1].fields.len(), 5);
+        assert_eq!(pages[1].title, "Title (cont.)");
+        assert!(pages[1].description.is_empty());
+    }
+
+    #[test]
+    fn test_paginate_and_group_by_length() {
+        let fields: Vec<EmbedField> = (0..10)
+            .flat_map(|i| EmbedField::split(&i.to_string(), &mentions(100), false))
+            .collect();
+
+        let pages = paginate_fields("Title", "", fields);
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(page.len() <= EMBED_TOTAL_LIMIT);
+            assert!(page.fields.len() <= EMBED_FIELD_COUNT_LIMIT);
+        }
+
+        let messages = group_pages(pages.clone());
+        assert_eq!(messages.iter().map(Vec::len).sum::<usize>(), pages.len());
+        for message in &messages {
+            assert!(message.len() <= EMBEDS_PER_MESSAGE_LIMIT);
+            assert!(message.iter().map(EmbedPage::len).sum::<usize>() <= EMBED_TOTAL_LIMIT);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// A value kept in a JSON file, so that bot state survives restarts.
+///
+/// The whole file is rewritten on every [`JsonStore::save`]; stores hold small
+/// amounts of state such as running giveaways or server settings.
+#[derive(Debug)]
+pub struct JsonStore<T> {
+    path: PathBuf,
+    value: Mutex<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Default> JsonStore<T> {
+    /// Loads the value from `path`. A missing file is the default value.
+    ///
+    /// # Errors
+    /// Returns an error when the file cannot be read or is not valid JSON.
+    pub fn load(path: PathBuf) -> Result<Self, crate::Error> {
+        let value = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => T::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, value: Mutex::new(value) })
+    }
+
+    /// Locks the value. Changes are only persisted by [`JsonStore::save`].
+    ///
+    /// Hold the lock until the change is saved when it depends on slow calls,
+    /// e.g. ending a giveaway, so that two callers never apply it twice.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        self.value.lock().await
+    }
+
+    /// Writes `value` to the file, replacing it atomically.
+    pub fn save(&self, value: &T) -> Result<(), crate::Error> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        // 書き込み途中で落ちても壊れないよう、一時ファイルから置き換える
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(value)?)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use poise::serenity_prelude::GuildId;
+
+    #[tokio::test]
+    async fn test_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("json_store_{}", std::process::id()));
+        let path = dir.join("store.json");
+        let _ = fs::remove_dir_all(&dir);
+
+        let store: JsonStore<HashMap<GuildId, Vec<u64>>> = JsonStore::load(path.clone()).unwrap();
+        assert!(store.lock().await.is_empty());
+
+        {
+            let mut value = store.lock().await;
+            value.insert(GuildId::new(100), vec![1, u64::MAX]);
+            store.save(&value).unwrap();
+        }
+
+        let reloaded: JsonStore<HashMap<GuildId, Vec<u64>>> = JsonStore::load(path).unwrap();
+        assert_eq!(reloaded.lock().await.get(&GuildId::new(100)), Some(&vec![1, u64::MAX]));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+/// Maximum number of characters Discord accepts in a message body.
+pub const MESSAGE_CHARACTER_LIMIT: usize = 2000;
+
+const CODE_FENCE: &str = "```";
+
+/// Splits `content` into chunks of at most `limit` characters.
+///
+/// Chunks are cut at the last line break or space that fits, falling back to a hard cut
+/// when there is none. A code block that is still open at the end of a chunk is closed
+/// there and reopened at the start of the next chunk, so every chunk renders on its own.
+///
+/// # Examples
+/// ```
+/// let chunks = split_message("aaaa bbbb cccc dddd", 10);
+/// assert_eq!(chunks, vec!["aaaa", "bbbb", "cccc dddd"]);
+/// ```
+pub fn split_message(content: &str, limit: usize) -> Vec<String> {
+    // 閉じ・開きのコードブロック記号を差し込む余地を残す
+    let budget = limit.saturating_sub(CODE_FENCE.len() * 2).max(1);
+
+    let mut chunks = Vec::new();
+    let mut remaining = content;
+    let mut is_fence_open = false;
+
+    while !remaining.is_empty() {
+        let prefix = if is_fence_open { CODE_FENCE } else { "" };
+
+        let (piece, rest) = if prefix.chars().count() + remaining.chars().count() <= limit {
+            (remaining, "")
+        } else {
+            split_at_boundary(remaining, budget)
+        };
+
+        let mut chunk = format!("{}{}", prefix, piece);
+        is_fence_open = chunk.matches(CODE_FENCE).count() % 2 == 1;
+        if is_fence_open && !rest.is_empty() {
+            chunk.push_str(CODE_FENCE);
+        }
+
+        chunks.push(chunk);
+        remaining = rest;
+    }
+
+    chunks
+}
+
+/// Cuts `text` after at most `budget` characters, preferring a line break, then a space.
+///
+/// The separator at the cut is dropped. Returns `(piece, rest)`.
+fn split_at_boundary(text: &str, budget: usize) -> (&str, &str) {
+    let end = char_index(text, budget);
+    // 区切り文字自体は切り捨てるので、1文字先まで探す
+    let window = &text[..char_index(text, budget + 1)];
+
+    if let Some(index) = window.rfind('\n').or_else(|| window.rfind(' ')).filter(|&i| i > 0) {
+        return (&text[..index], &text[index + 1..]);
+    }
+
+    // 区切りが無い場合はコードブロック記号の途中で切らないようにする
+    let mut index = end;
+    while index > 1 && text[..index].ends_with('`') {
+        index = text[..index].char_indices().last().map(|(i, _)| i).unwrap_or(0);
+    }
+    if index == 0 {
+        index = end;
+    }
+    (&text[..index], &text[index..])
+}
+
+/// Byte index of the `n`-th character, or the length of `text` when it is shorter.
+fn char_index(text: &str, n: usize) -> usize {
+    text.char_indices()
+        .nth(n)
+        .map(|(index, _)| index)
+        .unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_message_short_content() {
+        assert_eq!(split_message("hello", 2000), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_split_message_prefers_line_breaks_and_spaces() {
+        let chunks = split_message("aaaa bbbb\ncccc dddd", 16);
+        assert_eq!(chunks, vec!["aaaa bbbb", "cccc dddd"]);
+
+        let chunks = split_message("aaaa bbbb cccc dddd", 10);
+        assert_eq!(chunks, vec!["aaaa", "bbbb", "cccc dddd"]);
+    }
+
+    #[test]
+    fn test_split_message_reopens_code_block() {
+        let content = "```<@1> <@2> <@3> <@4>```";
+        let chunks = split_message(content, 20);
+
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 20, "{}", chunk);
+            assert_eq!(chunk.matches(CODE_FENCE).count() % 2, 0, "{}", chunk);
+        }
+        assert_eq!(chunks.concat().replace(CODE_FENCE, "").replace(' ', ""), "<@1><@2><@3><@4>");
+    }
+
+    #[test]
+    fn test_split_message_hard_cut_respects_char_boundaries() {
+        let content = "👍".repeat(30);
+        let chunks = split_message(&content, 16);
+
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 16));
+        assert_eq!(chunks.concat(), content);
+    }
+}
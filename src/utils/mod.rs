@@ -1,3 +1,8 @@
 pub mod parsers;
 pub mod url_parser;
-pub mod discord_helper;
\ No newline at end of file
+pub mod discord_helper;
+pub mod delimited;
+pub mod message_splitter;
+pub mod embed_splitter;
+pub mod config;
+pub mod json_store;
\ No newline at end of file
@@ -3,7 +3,6 @@ use regex::Regex;
 use crate::utils::url_parser::{is_url, try_parse_discord_url, IdType};
 
 /// Parse user mentions from a string containing mentions or user IDs
-#[allow(dead_code)]
 pub async fn parse_user_mentions(input: &str) -> Vec<u64> {
     let re = Regex::new(r"<@!?(\d+)>").unwrap();
 
@@ -14,8 +13,17 @@ pub async fn parse_user_mentions(input: &str) -> Vec<u64> {
         .collect()
 }
 
+/// Parse role mentions (`<@&id>`) from a string
+pub async fn parse_role_mentions(input: &str) -> Vec<u64> {
+    let re = Regex::new(r"<@&(\d+)>").unwrap();
+
+    re.captures_iter(input)
+        .filter_map(|cap| cap.get(1))
+        .filter_map(|m| m.as_str().parse::<u64>().ok())
+        .collect()
+}
+
 /// Parse reactions from a string containing reaction emojis or names
-#[allow(dead_code)]
 pub async fn parse_reactions(input: &str) -> Vec<String> {
     input.split(&[' ', ','])
         .map(|s| s.trim().to_string())
@@ -23,6 +31,49 @@ pub async fn parse_reactions(input: &str) -> Vec<String> {
         .collect()
 }
 
+/// Parse a duration such as `90s`, `45m`, `1h30m`, `2d` or `1w` into seconds.
+///
+/// Units are `s`, `m`, `h`, `d` and `w`, and may be combined in any order.
+/// A bare number is read as minutes.
+pub fn parse_duration(input: &str) -> Result<u64> {
+    let input = input.trim().to_ascii_lowercase();
+    if let Ok(minutes) = input.parse::<u64>() {
+        return minutes.checked_mul(60).ok_or_else(|| anyhow::anyhow!("Duration is too long"));
+    }
+
+    let re = Regex::new(r"^(?:\s*\d+\s*[smhdw])+$").unwrap();
+    if !re.is_match(&input) {
+        return Err(anyhow::anyhow!("Invalid duration '{}'. Use e.g. 30m, 1h30m or 2d.", input));
+    }
+
+    let part = Regex::new(r"(\d+)\s*([smhdw])").unwrap();
+    part.captures_iter(&input).try_fold(0u64, |total, cap| {
+        let unit = match &cap[2] {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => 7 * 24 * 60 * 60,
+        };
+        cap[1]
+            .parse::<u64>()
+            .ok()
+            .and_then(|value| value.checked_mul(unit))
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(|| anyhow::anyhow!("Duration is too long"))
+    })
+}
+
+/// Parse the seed of a reproducible draw, an unsigned 64-bit integer.
+///
+/// Seeds are taken as strings because Discord integer options only go up to 2^53.
+pub fn parse_seed(input: &str) -> Result<u64> {
+    input
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| anyhow::anyhow!("The seed must be an integer from 0 to {}.", u64::MAX))
+}
+
 /// Parse message identifier (URL or ID) and return (guild_id, channel_id, message_id)
 #[allow(dead_code)]
 pub async fn parse_message_identifier(input: &str) -> Result<u64> {
@@ -39,4 +90,28 @@ pub async fn parse_message_identifier(input: &str) -> Result<u64> {
     }
     
     Err(anyhow::anyhow!("Invalid message identifier"))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s").unwrap(), 90);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5400);
+        assert_eq!(parse_duration(" 2D ").unwrap(), 2 * 86400);
+        assert_eq!(parse_duration("1w 1d").unwrap(), 8 * 86400);
+        assert_eq!(parse_duration("15").unwrap(), 900);
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("1h later").is_err());
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed(" 18446744073709551615 ").unwrap(), u64::MAX);
+        assert!(parse_seed("-1").is_err());
+        assert!(parse_seed("lucky").is_err());
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum IdType {
     GuildId,